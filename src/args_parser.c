@@ -30,6 +30,82 @@ bool is_glob_pattern(const char *str) {
     return (strchr(str, '*') != NULL || strchr(str, '?') != NULL || strchr(str, '[') != NULL);
 }
 
+/**
+ * @brief Extracts the value of an option that takes an argument.
+ *
+ * Both "--name=VALUE" and "--name VALUE" forms are accepted. In the
+ * second form the index is advanced past the consumed value.
+ *
+ * @param argc The number of command-line arguments.
+ * @param argv An array of strings containing the command-line arguments.
+ * @param i Pointer to the index of the argument being examined.
+ * @param name The option name, including leading dashes.
+ * @return The option value, an empty string if the value is missing,
+ *         or NULL if the argument is not this option.
+ */
+static const char *get_option_value(int argc, char *argv[], int *i, const char *name) {
+    size_t name_len = strlen(name);
+    if (strncmp(argv[*i], name, name_len) != 0) {
+        return NULL;  // Not this option
+    }
+    if (argv[*i][name_len] == '=') {
+        return argv[*i] + name_len + 1;  // Value given as --name=VALUE
+    }
+    if (argv[*i][name_len] != '\0') {
+        return NULL;  // A different option sharing the same prefix
+    }
+    if (*i + 1 < argc) {
+        return argv[++(*i)];  // Value given as the next argument
+    }
+    return "";  // Option given without a value
+}
+
+/**
+ * @brief Parses a non-negative integer option value.
+ *
+ * @param value The string to parse.
+ * @param result Pointer to store the parsed number.
+ * @return true if the whole string is a valid non-negative number, false otherwise.
+ */
+static bool parse_count(const char *value, int *result) {
+    char *end;
+    long number = strtol(value, &end, 10);
+    if (*value == '\0' || *end != '\0' || number < 0 || number > 10000) {
+        return false;
+    }
+    *result = (int)number;
+    return true;
+}
+
+/**
+ * @brief Parses the value of the --sort option.
+ *
+ * @param value The sort key name.
+ * @param result Pointer to store the parsed sort key.
+ * @return true if the name is a known sort key, false otherwise.
+ */
+static bool parse_sort_key(const char *value, SortKey *result) {
+    static const struct {
+        const char *name;
+        SortKey key;
+    } sort_keys[] = {
+        {"ext", SORT_EXTENSION},
+        {"name", SORT_NAME},
+        {"size", SORT_SIZE},
+        {"time", SORT_TIME},
+        {"version", SORT_VERSION},
+        {"none", SORT_NONE},
+    };
+
+    for (size_t i = 0; i < sizeof(sort_keys) / sizeof(sort_keys[0]); i++) {
+        if (strcmp(value, sort_keys[i].name) == 0) {
+            *result = sort_keys[i].key;
+            return true;
+        }
+    }
+    return false;
+}
+
 /**
  * @brief Parses command-line arguments.
  *
@@ -44,10 +120,72 @@ CommandLineArgs parse_args(int argc, char *argv[]) {
     CommandLineArgs args = {0};  // Initialize the args structure with zeros
     args.targets = malloc(sizeof(char*) * (argc - 1));  // Allocate memory for targets
     args.target_count = 0;  // Initialize target count to 0
+    args.group_directories_first = true;  // Directories are listed first by default
+    args.width = -1;  // Use the terminal width unless --width is given
 
     // Loop through all arguments starting from index 1 (skip program name)
     for (int i = 1; i < argc; i++) {
-        if (strcmp(argv[i], "-v") == 0 || strcmp(argv[i], "--version") == 0) {
+        const char *value;
+        if ((value = get_option_value(argc, argv, &i, "--columns")) != NULL) {
+            if (!parse_count(value, &args.max_columns) || args.max_columns == 0) {
+                args.invalid_arg = value;  // Store the rejected value
+                args.invalid_arg_opt = "--columns";
+                return args;
+            }
+        } else if ((value = get_option_value(argc, argv, &i, "--width")) != NULL) {
+            if (!parse_count(value, &args.width)) {
+                args.invalid_arg = value;  // Store the rejected value
+                args.invalid_arg_opt = "--width";
+                return args;
+            }
+        } else if ((value = get_option_value(argc, argv, &i, "--sort")) != NULL) {
+            if (!parse_sort_key(value, &args.sort_key)) {
+                args.invalid_arg = value;  // Store the rejected value
+                args.invalid_arg_opt = "--sort";
+                return args;
+            }
+        } else if (strncmp(argv[i], "--group-directories-first", 25) == 0 &&
+                   (argv[i][25] == '\0' || argv[i][25] == '=')) {
+            // The value is optional, so it is only taken from the --name=VALUE form
+            value = argv[i][25] == '=' ? argv[i] + 26 : "true";
+            if (strcmp(value, "true") == 0) {
+                args.group_directories_first = true;
+            } else if (strcmp(value, "false") == 0) {
+                args.group_directories_first = false;
+            } else {
+                args.invalid_arg = value;  // Store the rejected value
+                args.invalid_arg_opt = "--group-directories-first";
+                return args;
+            }
+        } else if (strncmp(argv[i], "--icons", 7) == 0 && (argv[i][7] == '\0' || argv[i][7] == '=')) {
+            // The value is optional, so it is only taken from the --name=VALUE form
+            value = argv[i][7] == '=' ? argv[i] + 8 : "always";
+            if (strcmp(value, "auto") == 0) {
+                args.icons = ICONS_AUTO;
+            } else if (strcmp(value, "never") == 0) {
+                args.icons = ICONS_NEVER;
+            } else if (strcmp(value, "always") == 0) {
+                args.icons = ICONS_ALWAYS;
+            } else {
+                args.invalid_arg = value;  // Store the rejected value
+                args.invalid_arg_opt = "--icons";
+                return args;
+            }
+        } else if ((value = get_option_value(argc, argv, &i, "--level")) != NULL ||
+                   (value = get_option_value(argc, argv, &i, "-L")) != NULL) {
+            if (!parse_count(value, &args.tree_depth) || args.tree_depth == 0) {
+                args.invalid_arg = value;  // Store the rejected value
+                args.invalid_arg_opt = "--level";
+                return args;
+            }
+        } else if ((value = get_option_value(argc, argv, &i, "--filter")) != NULL) {
+            if (value[0] == '\0') {
+                args.invalid_arg = value;  // An empty pattern would match nothing
+                args.invalid_arg_opt = "--filter";
+                return args;
+            }
+            args.filter = value;
+        } else if (strcmp(argv[i], "-v") == 0 || strcmp(argv[i], "--version") == 0) {
             args.show_version = true;  // Set flag to show version
         } else if (strcmp(argv[i], "-h") == 0 || strcmp(argv[i], "--help") == 0) {
             args.show_help = true;  // Set flag to show help
@@ -55,6 +193,32 @@ CommandLineArgs parse_args(int argc, char *argv[]) {
             args.show_longlisting = true;  // Set flag for long listing
         } else if (strcmp(argv[i], "-a") == 0 || strcmp(argv[i], "--analytics") == 0) {
             args.show_dir_analytics = true;  // Set flag for directory analytics
+        } else if (strcmp(argv[i], "-A") == 0 || strcmp(argv[i], "--all") == 0) {
+            args.show_all = true;  // Set flag to include hidden files
+        } else if (strcmp(argv[i], "-t") == 0) {
+            args.sort_key = SORT_TIME;  // Sort by modification time
+        } else if (strcmp(argv[i], "-r") == 0 || strcmp(argv[i], "--reverse") == 0) {
+            args.reverse_sort = true;  // Set flag to reverse the sort order
+        } else if (strcmp(argv[i], "-p") == 0 || strcmp(argv[i], "--plain") == 0) {
+            args.icons = ICONS_NEVER;  // Omit emojis
+        } else if (strcmp(argv[i], "-C") == 0) {
+            args.force_columns = true;  // Set flag to keep the grid when piped
+        } else if (strcmp(argv[i], "-x") == 0) {
+            args.across = true;  // Set flag to fill rows instead of columns
+        } else if (strcmp(argv[i], "-R") == 0 || strcmp(argv[i], "--recursive") == 0) {
+            args.recursive = true;  // Set flag to list subdirectories recursively
+        } else if (strcmp(argv[i], "-d") == 0 || strcmp(argv[i], "--directory") == 0) {
+            args.directory_itself = true;  // Set flag to list directories themselves
+        } else if (strcmp(argv[i], "-T") == 0 || strcmp(argv[i], "--totals") == 0) {
+            args.show_totals = true;  // Set flag to print a summary line
+        } else if (strcmp(argv[i], "--no-truncate") == 0) {
+            args.no_truncate = true;  // Set flag to print long names in full
+        } else if (strcmp(argv[i], "--tree") == 0) {
+            args.tree = true;  // Set flag to print a tree
+        } else if (strcmp(argv[i], "--summary") == 0) {
+            args.emoji_summary = true;  // Set flag to count entries per emoji
+        } else if (strcmp(argv[i], "--json") == 0) {
+            args.json = true;  // Set flag to print JSON instead of the grid
         } else if (argv[i][0] == '-') {
             args.invalid_opt = argv[i];  // Store invalid option
             return args;  // Return immediately on invalid option
@@ -100,12 +264,38 @@ void print_version(void) {
  * @param program_name The name of the program executable.
  */
 void print_help(const char *program_name) {
-    printf("Usage: %s [OPTION] [DIRECTORY]\n", program_name);
+    printf("Usage: %s [OPTION]... [FILE]...\n", program_name);
     printf("List directory contents with colorful emojis.\n\n");
     printf("Options:\n");
     printf("  -h, --help      display this help and exit\n");
     printf("  -v, --version   output version information and exit\n");
     printf("  -l, --long      display detailed directory listing\n");
     printf("  -a, --analytics display directory analytics\n");
-    printf("\nIf DIRECTORY is not specified, the current directory is used.\n");
+    printf("  -A, --all       do not ignore entries starting with .\n");
+    printf("      --columns N use at most N columns in the grid layout\n");
+    printf("      --width N   lay the grid out for N columns of text instead of the terminal\n");
+    printf("                  width, 0 for no limit\n");
+    printf("      --sort KEY  sort by KEY: ext (default), name, size, time, version or none\n");
+    printf("      --group-directories-first[=BOOL]\n");
+    printf("                  list directories before files (default true)\n");
+    printf("  -t              sort by modification time, newest first (same as --sort=time)\n");
+    printf("  -r, --reverse   reverse order while sorting\n");
+    printf("  -p, --plain     print names without emojis (same as --icons=never)\n");
+    printf("      --icons[=WHEN]  print emojis 'always' (default WHEN), 'auto' or 'never';\n");
+    printf("                  'auto' prints them on a terminal with a UTF-8 locale;\n");
+    printf("                  by default they are left out only of one-name-per-line output\n");
+    printf("  -C              list entries in columns even when output is not a terminal\n");
+    printf("  -x              list entries by lines instead of by columns\n");
+    printf("  -R, --recursive list subdirectories recursively\n");
+    printf("  -d, --directory list directories themselves, not their contents\n");
+    printf("  -T, --totals    print counts of directories, files and symlinks with total size\n");
+    printf("      --no-truncate  print names wider than the terminal in full\n");
+    printf("      --filter GLOB  only list entries whose names match the shell pattern GLOB\n");
+    printf("      --tree      print directories as a tree\n");
+    printf("  -L, --level N   descend at most N levels in the tree\n");
+    printf("      --summary   print the number of entries of each kind instead of the entries\n");
+    printf("      --json      print entries as a JSON array instead of the grid\n");
+    printf("\nIf no FILE is specified, the current directory is used. With several FILEs,\n");
+    printf("files are listed first, then each directory under its own header.\n");
+    printf("When output is not a terminal, one plain name is printed per line.\n");
 }
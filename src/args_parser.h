@@ -15,6 +15,32 @@
 
 #include <stdbool.h>  // Include for boolean type
 
+/**
+ * @enum SortKey
+ * @brief Order in which entries are listed.
+ */
+typedef enum
+{
+    SORT_EXTENSION = 0, // Directories first, then files grouped by extension (default)
+    SORT_NAME,          // Directories first, then alphabetically by name
+    SORT_SIZE,          // Directories first, then largest files first
+    SORT_TIME,          // Directories first, then newest entries first
+    SORT_VERSION,       // Directories first, then by name with numbers compared by value
+    SORT_NONE           // Directory order, unsorted
+} SortKey;
+
+/**
+ * @enum IconsMode
+ * @brief When emojis are printed next to names.
+ */
+typedef enum
+{
+    ICONS_DEFAULT = 0, // Always, except in the plain one-name-per-line output (default)
+    ICONS_AUTO,     // Only on a terminal with a UTF-8 locale
+    ICONS_NEVER,    // Never, same as --plain
+    ICONS_ALWAYS    // Always, even when output is piped
+} IconsMode;
+
 /**
  * @struct CommandLineArgs
  * @brief Structure to hold parsed command-line arguments.
@@ -28,7 +54,27 @@ typedef struct
     bool show_help;           // Flag to show help information
     bool show_longlisting;    // Flag to show detailed directory listing
     bool show_dir_analytics;  // Flag to show directory analytics
+    bool show_all;            // Flag to include hidden (dot) files
+    bool reverse_sort;        // Flag to reverse the sort order
+    bool force_columns;       // Flag to use the grid layout even when not writing to a terminal
+    bool across;              // Flag to fill grid rows left to right instead of columns
+    bool recursive;           // Flag to list subdirectories recursively
+    bool directory_itself;    // Flag to list directory arguments as entries instead of their contents
+    bool show_totals;         // Flag to print a summary line after the listing
+    bool json;                // Flag to print entries as a JSON array
+    bool emoji_summary;       // Flag to print counts per emoji instead of entries
+    bool tree;                // Flag to print directories as a tree
+    int tree_depth;           // Maximum depth of the tree (0 means unlimited)
+    bool no_truncate;         // Flag to print long names in full instead of cutting them
+    int max_columns;          // Maximum number of grid columns (0 means default)
+    int width;                // Output width in columns (0 means unlimited, -1 means detect)
+    SortKey sort_key;         // Order in which entries are listed
+    IconsMode icons;          // When emojis are printed next to names
+    bool group_directories_first; // Flag to list directories before files (on by default)
+    const char *filter;       // Glob pattern entry names must match, or NULL to show all
     const char *invalid_opt;  // Stores any invalid option encountered
+    const char *invalid_arg;  // Stores an invalid value given to a valid option
+    const char *invalid_arg_opt; // Name of the option that received the invalid value
     const char **targets;     // Array of target directories or files
     int target_count;         // Number of targets specified
 } CommandLineArgs;
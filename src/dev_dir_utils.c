@@ -40,12 +40,13 @@ int is_dev_directory(const char *path)
  * This function reads the entries in the /dev directory, creates FileCardInfo
  * structures for each entry, and stores them in the provided array.
  *
+ * @param show_hidden Include entries whose names start with a dot
  * @param entries Pointer to an array of FileCardInfo pointers
  * @param num_entries Pointer to the number of entries in the array
  * @param current_size Pointer to the current size of the array
  * @return 0 on success, -1 on failure
  */
-int handle_dev_directory(FileCardInfo **entries, int *num_entries, int *current_size)
+int handle_dev_directory(int show_hidden, FileCardInfo **entries, int *num_entries, int *current_size)
 {
     DIR *dir = opendir("/dev");  // Open the /dev directory
     if (!dir)
@@ -76,6 +77,11 @@ int handle_dev_directory(FileCardInfo **entries, int *num_entries, int *current_
             continue; // Skip the . and .. entries
         }
 
+        if (!show_hidden && d_entry->d_name[0] == '.')
+        {
+            continue; // Skip hidden entries unless requested
+        }
+
         // Check if we need to allocate more memory
         if (*num_entries >= *current_size)
         {
@@ -94,23 +100,46 @@ int handle_dev_directory(FileCardInfo **entries, int *num_entries, int *current_
         // Construct the full path to the file in /dev
         snprintf(full_path, sizeof(full_path), "/dev/%s", d_entry->d_name);
 
-        if (lstat(full_path, &st) == 0)  // Get file status
+        if (lstat(full_path, &st) != 0)  // Get file status
+        {
+            // Devices can disappear while the directory is read; still list them
+            fprintf(stderr, "facad: cannot access '%s': %s\n", full_path, strerror(errno));
+            if (create_unknown_file_entry(&((*entries)[*num_entries]), d_entry->d_name) == 0)
+            {
+                (*num_entries)++;
+            }
+        }
+        else
         {
             FileCardInfo *entry = &((*entries)[*num_entries]);  // Get pointer to current entry
             entry->name = strdup(d_entry->d_name);  // Duplicate the entry name
-            if (entry->name == NULL)
+            entry->display_name = make_display_name(d_entry->d_name);  // Printable version of the name
+            entry->path = strdup(full_path);  // Already a normalized path
+            if (entry->name == NULL || entry->display_name == NULL || entry->path == NULL)
             {
                 perror("strdup");  // Print error message if strdup fails
+                free(entry->name);
+                free(entry->display_name);
+                free(entry->path);
                 closedir(dir);  // Close the directory
                 return -1;  // Return -1 to indicate failure
             }
 
             entry->is_directory = S_ISDIR(st.st_mode);  // Check if it's a directory
             entry->is_hidden = (d_entry->d_name[0] == '.');  // Check if it's a hidden file
+            entry->mode = st.st_mode;  // Store the file type and permissions
+            struct stat target_st;
+            entry->target_mode = (S_ISLNK(st.st_mode) && stat(full_path, &target_st) == 0) ? target_st.st_mode : 0;
+            entry->size = st.st_size;  // Store the size reported by lstat
+            entry->mtime = st.st_mtime;  // Store the modification time
+            entry->git_status[0] = '\0';  // Devices are never tracked by Git
+            entry->git_status[1] = '\0';
+            entry->git_staged = 0;
+            entry->contains_modified = 0;
 
             if (S_ISLNK(st.st_mode))  // Check if it's a symbolic link
             {
-                entry->emoji = strdup(entry->is_directory ? "🔗📁" : "🔗");  // Use link emoji
+                entry->emoji = get_symlink_emoji(entry->target_mode);  // Use link emoji based on the target
             }
             else if (entry->is_directory)
             {
@@ -125,6 +154,7 @@ int handle_dev_directory(FileCardInfo **entries, int *num_entries, int *current_
             {
                 fprintf(stderr, "Failed to allocate memory for emoji: %s\n", strerror(errno));  // Print error message
                 free(entry->name);  // Free the allocated name
+                free(entry->display_name);  // Free the printable name
                 closedir(dir);  // Close the directory
                 return -1;  // Return -1 to indicate failure
             }
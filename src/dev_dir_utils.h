@@ -38,12 +38,13 @@ extern "C"
      * in the provided array. It handles memory allocation and reallocation
      * as needed.
      *
+     * @param show_hidden Include entries whose names start with a dot
      * @param entries Double pointer to an array of FileCardInfo structures that will be populated
      * @param num_entries Pointer to the current number of entries in the array
      * @param current_size Pointer to the current size of the array
      * @return 0 on success, -1 on failure
      */
-    int handle_dev_directory(FileCardInfo **entries, int *num_entries, int *current_size);
+    int handle_dev_directory(int show_hidden, FileCardInfo **entries, int *num_entries, int *current_size);
 
 #ifdef __cplusplus
 }
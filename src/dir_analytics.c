@@ -24,37 +24,11 @@
 
 #include "emoji_utils.h"
 #include "dir_analytics.h"
+#include "display_utils.h"
 
 #define MAX_PATH 4096
 #define MAX_FILES 1024
 
-// ANSI escape codes for text formatting
-#define ANSI_BOLD "\033[1m"
-#define ANSI_RESET "\033[0m"
-
-/**
- * @brief Formats a file size into a human-readable string.
- *
- * @param size The size in bytes to format.
- * @return A pointer to a static buffer containing the formatted size string.
- */
-static char* format_size(off_t size) {
-    static char buf[64];
-    const char* units[] = {"B", "K", "M", "G", "T", "P", "E", "Z", "Y"};
-    int i = 0;
-    double dsize = size;
-
-    // Convert size to appropriate unit
-    while (dsize >= 1024 && i < 8) {
-        dsize /= 1024;
-        i++;
-    }
-
-    // Format the size with one decimal place
-    snprintf(buf, sizeof(buf), "%.1f%s", dsize, units[i]);
-    return buf;
-}
-
 /**
  * @brief Formats a time_t value into a human-readable string.
  *
@@ -207,8 +181,9 @@ static void find_largest_file(const char* path, off_t* largest_size, char* large
  * directory depth, and information about largest and newest files.
  *
  * @param path The path of the directory to analyze.
+ * @return 0 on success, -1 if the directory cannot be read.
  */
-void print_dir_analytics(const char* path) {
+int print_dir_analytics(const char* path) {
     DIR* dir;
     struct dirent* entry;
     struct stat st;
@@ -221,8 +196,8 @@ void print_dir_analytics(const char* path) {
 
     dir = opendir(path);
     if (!dir) {
-        perror("Error opening directory");
-        return;
+        fprintf(stderr, "facad: cannot open '%s': %s\n", path, strerror(errno));
+        return -1;
     }
 
     // Find the largest file recursively
@@ -277,11 +252,11 @@ void print_dir_analytics(const char* path) {
     char cwd[MAX_PATH];
     if (getcwd(cwd, sizeof(cwd)) == NULL) {
         perror("getcwd() error");
-        return;
+        return -1;
     }
 
     // Print analytics
-    printf("%s%s%s\n", ANSI_BOLD, cwd, ANSI_RESET);
+    printf("%s%s%s\n", ansi_style(ANSI_BOLD), cwd, ansi_style(ANSI_RESET));
     printf("🧮 Total Size    : %s\n", format_size(total_size));
     printf("🗂️  Directories   : %d\n", total_dirs);
     printf("🌳 Max Depth     : %d levels\n", max_depth);
@@ -297,4 +272,5 @@ void print_dir_analytics(const char* path) {
         printf("%s%s", empty_file_names[i], (i + 1 < empty_files) ? " " : "");
     }
     printf("]\n");
+    return 0;
 }
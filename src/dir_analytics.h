@@ -31,6 +31,7 @@
  * contents, useful for storage management and content analysis.
  *
  * @param path The path of the directory to analyze
+ * @return 0 on success, -1 if the directory cannot be read
  *
  * @note This function performs a recursive analysis and may take
  *       considerable time for large directory structures.
@@ -38,6 +39,6 @@
  * @warning For very large directories, this function may consume
  *          significant memory and processing resources.
  */
-void print_dir_analytics(const char *path);
+int print_dir_analytics(const char *path);
 
 #endif // DIR_ANALYTICS_H
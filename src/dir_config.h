@@ -14,11 +14,11 @@
 #define DIRECTORY_CONFIG_H
 
 /**
- * @brief Default maximum number of columns for directory listings.
+ * @brief Column limit of directories without a configuration of their own.
  *
- * This value is used when no specific configuration is found for a directory.
+ * 0 means no limit: as many columns are used as fit the terminal width.
  */
-#define DEFAULT_MAX_COLUMNS 4
+#define DEFAULT_MAX_COLUMNS 0
 
 /**
  * @struct DirectoryConfig
@@ -56,7 +56,8 @@ extern const int num_directory_configs;
  * custom configurations and returns the default if none is found.
  *
  * @param path The path of the directory to check.
- * @return The maximum number of columns for the directory listing.
+ * @return The maximum number of columns for the directory listing, or
+ *         DEFAULT_MAX_COLUMNS (no limit) if the directory has none.
  */
 int get_max_columns(const char *path);
 
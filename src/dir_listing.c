@@ -0,0 +1,127 @@
+/**
+ * @file dir_listing.c
+ * @brief Implementation of reading directory contents into file cards.
+ *
+ * This file contains the functions that open a directory, create a
+ * FileCardInfo structure for each of its entries and attach Git status
+ * information to them.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#include <stdio.h>
+#include <errno.h>
+#include <stdlib.h>
+#include <string.h>
+#include <dirent.h>
+
+#include "dir_listing.h"
+#include "git_integration.h"
+
+/**
+ * @brief Dynamically allocates or reallocates memory for directory entries.
+ *
+ * This function manages the memory for the array of FileCardInfo structures.
+ * It starts with INITIAL_ENTRIES and doubles the size when more space is needed.
+ *
+ * @param entries Pointer to the array of FileCardInfo structures.
+ * @param current_size Pointer to the current size of the array.
+ * @return 0 on success, -1 on failure.
+ */
+int allocate_entries(FileCardInfo **entries, int *current_size) {
+    // Calculate new size (double the current size or use initial size if NULL)
+    int new_size = (*entries == NULL) ? INITIAL_ENTRIES : (*current_size * 2);
+
+    // Attempt to reallocate memory for entries
+    FileCardInfo *new_entries = realloc(*entries, new_size * sizeof(FileCardInfo));
+
+    // Check if reallocation was successful
+    if (new_entries == NULL) {
+        perror("Failed to allocate memory for entries"); // Print error message
+        return -1;  // Return -1 to indicate failure
+    }
+
+    // Update entries pointer and current size
+    *entries = new_entries;
+    *current_size = new_size;
+    return 0;  // Return 0 to indicate success
+}
+
+/**
+ * @brief Processes directory entries and populates the entries array.
+ *
+ * This function reads directory contents, creates FileCardInfo structures for each entry,
+ * and dynamically allocates memory as needed. It also integrates Git status information.
+ *
+ * @param dir_path Path to the directory to process.
+ * @param show_hidden Include entries whose names start with a dot.
+ * @param entries Pointer to the array of FileCardInfo structures.
+ * @param num_entries Pointer to the number of entries processed.
+ * @param current_size Pointer to the current size of the entries array.
+ * @return 0 on success, -1 on failure.
+ */
+int list_directory(const char *dir_path, bool show_hidden, FileCardInfo **entries, int *num_entries, int *current_size) {
+    DIR *dir = opendir(dir_path); // Open the directory
+    if (dir == NULL) {
+        // Print a short message like "facad: cannot open 'foo': Permission denied"
+        fprintf(stderr, "facad: cannot open '%s': %s\n", dir_path, strerror(errno));
+        return -1;
+    }
+
+    struct dirent *entry;
+    while ((entry = readdir(dir)) != NULL) { // Loop through each entry in the directory
+        // Skip '.' and '..' entries
+        if (strcmp(entry->d_name, ".") == 0 || strcmp(entry->d_name, "..") == 0) {
+            continue;
+        }
+
+        // Skip hidden entries unless they were requested
+        if (!show_hidden && entry->d_name[0] == '.') {
+            continue;
+        }
+
+        // Check if we need to allocate more memory
+        if (*num_entries >= *current_size) {
+            if (allocate_entries(entries, current_size) != 0) {
+                closedir(dir); // Close the directory before returning
+                return -1;
+            }
+        }
+
+        // Construct full path for the current entry
+        char full_path[MAX_PATH];
+        snprintf(full_path, sizeof(full_path), "%s/%s", dir_path, entry->d_name);
+
+        // Create a file entry and add it to the array; entries that cannot be
+        // examined are reported and still listed with a placeholder
+        if (create_file_entry_in(&(*entries)[*num_entries], dir_path, entry->d_name) != 0) {
+            fprintf(stderr, "facad: cannot access '%s': %s\n", full_path, strerror(errno));
+            if (create_unknown_file_entry(&(*entries)[*num_entries], entry->d_name) != 0) {
+                continue;
+            }
+        }
+
+        (*num_entries)++; // Increment the number of entries
+    }
+
+    closedir(dir); // Close the directory
+
+    // Integrate Git status information for the entries
+    integrate_git_status(entries, num_entries, dir_path);
+
+    return 0; // Return 0 to indicate success
+}
+
+/**
+ * @brief Frees an array of entries returned by list_directory.
+ *
+ * @param entries The array of FileCardInfo structures.
+ * @param num_entries The number of entries in the array.
+ */
+void free_entries(FileCardInfo *entries, int num_entries) {
+    for (int i = 0; i < num_entries; i++) {
+        free_file_entry(&entries[i]);
+    }
+    free(entries);
+}
@@ -0,0 +1,71 @@
+/**
+ * @file dir_listing.h
+ * @brief Reading directory contents into file cards.
+ *
+ * This header file declares the functions used to collect the entries of a
+ * directory into a dynamically grown array of FileCardInfo structures. They
+ * are the listing half of the facad library API; display_utils.h provides
+ * the rendering half.
+ *
+ * The library is not reentrant: emoji choices and LS_COLORS colors come
+ * from process-wide tables filled by load_emoji_config() and
+ * load_ls_colors(), so calls must not run concurrently with those loaders.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#ifndef DIR_LISTING_H
+#define DIR_LISTING_H
+
+#include <stdbool.h>
+
+#include "file_card.h"
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define INITIAL_ENTRIES 512  // Initial number of directory entries to allocate
+
+/**
+ * @brief Dynamically allocates or reallocates memory for directory entries.
+ *
+ * The array starts with INITIAL_ENTRIES and doubles in size when more
+ * space is needed.
+ *
+ * @param entries Pointer to the array of FileCardInfo structures.
+ * @param current_size Pointer to the current size of the array.
+ * @return 0 on success, -1 on failure.
+ */
+int allocate_entries(FileCardInfo **entries, int *current_size);
+
+/**
+ * @brief Reads the entries of a directory and appends them to an array.
+ *
+ * Entries that cannot be examined are reported on stderr and added with a
+ * placeholder. Git status information is attached to the entries when the
+ * directory is inside a repository. The entries are not sorted.
+ *
+ * @param dir_path Path to the directory to list.
+ * @param show_hidden Include entries whose names start with a dot.
+ * @param entries Pointer to the array of FileCardInfo structures, NULL to start a new one.
+ * @param num_entries Pointer to the number of entries in the array.
+ * @param current_size Pointer to the allocated size of the array.
+ * @return 0 on success, -1 if the directory cannot be opened or memory runs out.
+ */
+int list_directory(const char *dir_path, bool show_hidden, FileCardInfo **entries, int *num_entries, int *current_size);
+
+/**
+ * @brief Frees an array of entries returned by list_directory.
+ *
+ * @param entries The array of FileCardInfo structures.
+ * @param num_entries The number of entries in the array.
+ */
+void free_entries(FileCardInfo *entries, int num_entries);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DIR_LISTING_H */
@@ -15,19 +15,46 @@
 #include <wchar.h>
 #include <string.h>
 #include <stdlib.h>
-#include <locale.h>
+#include <stdint.h>
+#include <unistd.h>
+#include <sys/stat.h>
 
 #include "file_card.h"
 #include "git_utils.h"
 #include "dir_config.h"
 #include "display_utils.h"
+#include "ls_colors.h"
 
 
-#define MAX_COLUMNS 4
 #define SPACING 2
-#define EMOJI_WIDTH 2
 #define EMOJI_NAME_SPACING 1
 
+/**
+ * @brief Check whether ANSI styling is enabled
+ *
+ * The decision is made once and cached for the rest of the run.
+ *
+ * @return 1 if escape codes should be printed, 0 otherwise
+ */
+int ansi_enabled(void) {
+    static int styling_enabled = -1;  // -1 until the environment has been checked
+    if (styling_enabled < 0) {
+        const char *no_color = getenv("NO_COLOR");
+        styling_enabled = isatty(STDOUT_FILENO) && !(no_color && no_color[0] != '\0');
+    }
+    return styling_enabled;
+}
+
+/**
+ * @brief Return an ANSI escape code if styling is enabled
+ *
+ * @param code The escape code to emit
+ * @return The code itself, or an empty string when styling is disabled
+ */
+const char *ansi_style(const char *code) {
+    return ansi_enabled() ? code : "";
+}
+
 /**
  * @brief Calculate the width of a UTF-8 character
  *
@@ -36,10 +63,107 @@
  */
 size_t get_utf8_char_width(const char *str) {
     unsigned char c = (unsigned char)*str;  // Get the first byte of the character
+    size_t len;
     if (c < 0x80) return 1;  // ASCII character (1 byte)
-    if (c < 0xE0) return 2;  // 2-byte UTF-8 character
-    if (c < 0xF0) return 3;  // 3-byte UTF-8 character
-    return 4;  // 4-byte UTF-8 character
+    if (c < 0xE0) len = 2;  // 2-byte UTF-8 character
+    else if (c < 0xF0) len = 3;  // 3-byte UTF-8 character
+    else len = 4;  // 4-byte UTF-8 character
+
+    // Treat truncated sequences as single bytes so we never step past the terminator
+    for (size_t i = 1; i < len; i++) {
+        if (((unsigned char)str[i] & 0xC0) != 0x80) return 1;
+    }
+    return len;
+}
+
+/**
+ * @brief Decode the code point of a UTF-8 character
+ *
+ * @param str Pointer to the start of a UTF-8 character
+ * @param len Size of the character in bytes, as returned by get_utf8_char_width
+ * @return The Unicode code point
+ */
+static unsigned int decode_utf8_char(const char *str, size_t len) {
+    const unsigned char *s = (const unsigned char *)str;
+    if (len == 1) return s[0];
+    if (len == 2) return ((s[0] & 0x1F) << 6) | (s[1] & 0x3F);
+    if (len == 3) return ((s[0] & 0x0F) << 12) | ((s[1] & 0x3F) << 6) | (s[2] & 0x3F);
+    return ((s[0] & 0x07) << 18) | ((s[1] & 0x3F) << 12) | ((s[2] & 0x3F) << 6) | (s[3] & 0x3F);
+}
+
+/**
+ * @brief Check if a code point takes no space on its own
+ *
+ * Covers combining marks, zero-width spaces and joiners, variation
+ * selectors and emoji skin tone modifiers.
+ *
+ * @param cp The Unicode code point
+ * @return 1 if the code point has zero display width, 0 otherwise
+ */
+static int is_zero_width(unsigned int cp) {
+    return (cp >= 0x0300 && cp <= 0x036F) ||   // Combining diacritical marks
+           (cp >= 0x200B && cp <= 0x200F) ||   // Zero-width space, joiners and marks
+           cp == 0x2060 ||                     // Word joiner
+           (cp >= 0x20D0 && cp <= 0x20FF) ||   // Combining marks for symbols (e.g. keycaps)
+           (cp >= 0xFE00 && cp <= 0xFE0F) ||   // Variation selectors
+           (cp >= 0x1F3FB && cp <= 0x1F3FF) || // Emoji skin tone modifiers
+           (cp >= 0xE0000 && cp <= 0xE01EF);   // Tags and supplementary variation selectors
+}
+
+/**
+ * @brief Check if a 3-byte code point is an East Asian wide character
+ *
+ * @param cp The Unicode code point
+ * @return 1 if the code point is displayed with width 2, 0 otherwise
+ */
+static int is_wide_bmp_char(unsigned int cp) {
+    return (cp >= 0x1100 && cp <= 0x115F) ||   // Hangul Jamo
+           (cp >= 0x2E80 && cp <= 0xA4CF) ||   // CJK radicals through Yi
+           (cp >= 0xAC00 && cp <= 0xD7A3) ||   // Hangul syllables
+           (cp >= 0xF900 && cp <= 0xFAFF) ||   // CJK compatibility ideographs
+           (cp >= 0xFE30 && cp <= 0xFE4F) ||   // CJK compatibility forms
+           (cp >= 0xFF00 && cp <= 0xFF60) ||   // Fullwidth forms
+           (cp >= 0xFFE0 && cp <= 0xFFE6);     // Fullwidth signs
+}
+
+/**
+ * @brief Calculate the display width of the character at the start of a string
+ *
+ * Zero-width characters that follow, such as combining marks and variation
+ * selectors, and characters joined with a zero-width joiner (e.g. the
+ * members of a family emoji) are taken together with the character, so
+ * they are never split from it.
+ *
+ * @param str Pointer to the start of a UTF-8 character, not the terminator
+ * @param length Output for the number of bytes taken together
+ * @return Display width of the character in columns
+ */
+size_t char_display_width(const char *str, size_t *length) {
+    size_t char_len = get_utf8_char_width(str);  // Get the byte width of the first character
+    unsigned int cp = decode_utf8_char(str, char_len);
+
+    size_t width;
+    if (is_zero_width(cp)) {
+        width = 0;  // Stray combining mark or joiner
+    } else if (char_len == 4 || (char_len == 3 && is_wide_bmp_char(cp))) {
+        width = 2;  // Emoji, CJK and other wide characters take two columns
+    } else {
+        width = 1;  // ASCII and other 2 and 3-byte UTF-8 characters have a width of 1
+    }
+
+    // Absorb what is composed into this character
+    size_t total = char_len;
+    int joined = (cp == 0x200D);
+    while (str[total]) {
+        size_t next_len = get_utf8_char_width(str + total);
+        unsigned int next = decode_utf8_char(str + total, next_len);
+        if (!joined && !is_zero_width(next)) break;
+        joined = (next == 0x200D);
+        total += next_len;
+    }
+
+    *length = total;
+    return width;
 }
 
 /**
@@ -51,19 +175,38 @@ size_t get_utf8_char_width(const char *str) {
 size_t get_display_width(const char *str) {
     size_t width = 0;  // Initialize the display width
     while (*str) {  // Loop through each character in the string
-        size_t char_width = get_utf8_char_width(str);  // Get the byte width of the current character
-        if (char_width == 4) {
-            width += 2;  // 4-byte UTF-8 characters are typically displayed as width 2
-        } else if (char_width > 1) {
-            width += 1;  // 2 and 3-byte UTF-8 characters are typically displayed as width 1
-        } else {
-            width += 1;  // ASCII characters have a width of 1
-        }
-        str += char_width;  // Move to the next character
+        size_t length;
+        width += char_display_width(str, &length);
+        str += length;  // Move to the next character
     }
     return width;  // Return the total display width
 }
 
+/**
+ * @brief Find the longest prefix of a UTF-8 string that fits a display width
+ *
+ * Characters are never split, and zero-width characters such as combining
+ * marks or joined emoji stay with the character they belong to.
+ *
+ * @param str The UTF-8 string to measure
+ * @param max_width The available display width
+ * @param prefix_width Output for the display width of the prefix
+ * @return Length of the prefix in bytes
+ */
+size_t fit_to_width(const char *str, size_t max_width, size_t *prefix_width) {
+    const char *start = str;
+    size_t width = 0;
+    while (*str) {
+        size_t length;
+        size_t cell_width = char_display_width(str, &length);
+        if (width + cell_width > max_width) break;
+        width += cell_width;
+        str += length;
+    }
+    *prefix_width = width;
+    return str - start;
+}
+
 /**
  * @brief Print a string with padding to a specified width
  *
@@ -79,97 +222,608 @@ void print_padded(const char *str, size_t width) {
 }
 
 /**
- * @brief Display directory entries in a grid layout
+ * @brief Get the index of the first entry in a column
+ *
+ * Entries are spread as evenly as possible: the first
+ * (num_entries % num_columns) columns hold one entry more than the rest,
+ * so no column is more than one entry longer than another.
+ *
+ * @param col The column to look up
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @return Index of the first entry in the column
+ */
+int column_start(int col, int num_entries, int num_columns) {
+    int base = num_entries / num_columns;
+    int extra = num_entries % num_columns;
+    return col * base + (col < extra ? col : extra);
+}
+
+/**
+ * @brief Get the number of entries in a column
+ *
+ * @param col The column to look up
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @return Number of entries in the column
+ */
+int column_length(int col, int num_entries, int num_columns) {
+    return num_entries / num_columns + (col < num_entries % num_columns ? 1 : 0);
+}
+
+/**
+ * @brief Get the number of rows of a grid
+ *
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @param across Whether entries fill rows left to right instead of columns
+ * @return Number of rows in the grid
+ */
+int grid_rows(int num_entries, int num_columns, int across) {
+    if (across) {
+        return (num_entries + num_columns - 1) / num_columns;
+    }
+    return column_length(0, num_entries, num_columns);  // The first column is always the longest one
+}
+
+/**
+ * @brief Get the entry shown in a grid cell
+ *
+ * Entries fill columns top to bottom, or rows left to right with across.
+ *
+ * @param row The row of the cell
+ * @param col The column of the cell
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @param across Whether entries fill rows left to right instead of columns
+ * @return Index of the entry, or -1 if the cell is empty
+ */
+static int grid_index(int row, int col, int num_entries, int num_columns, int across) {
+    if (col >= num_columns) {
+        return -1;
+    }
+    if (across) {
+        int index = row * num_columns + col;
+        return index < num_entries ? index : -1;
+    }
+    if (row >= column_length(col, num_entries, num_columns)) {
+        return -1;
+    }
+    return column_start(col, num_entries, num_columns) + row;
+}
+
+/**
+ * @brief Calculate column widths for a grid with the given number of columns
+ *
+ * @param entry_widths Display width of every entry
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @param across Whether entries fill rows left to right instead of columns
+ * @param column_widths Output array of num_columns widths
+ * @param limit Stop measuring once the grid is wider than this
+ * @return Total width of the grid including spacing between columns, or
+ *         a width above limit if the grid does not fit
+ */
+static size_t calculate_column_widths(const size_t *entry_widths, int num_entries, int num_columns,
+                                      int across, size_t *column_widths, size_t limit) {
+    size_t total_width = 0;
+    int rows = grid_rows(num_entries, num_columns, across);
+
+    // Each column is as wide as its widest entry
+    for (int col = 0; col < num_columns; col++) {
+        column_widths[col] = 0;
+        for (int row = 0; row < rows; row++) {
+            int index = grid_index(row, col, num_entries, num_columns, across);
+            if (index >= 0 && entry_widths[index] > column_widths[col]) {
+                column_widths[col] = entry_widths[index];
+            }
+        }
+        total_width += column_widths[col] + (col > 0 ? SPACING : 0);
+        if (total_width > limit) {
+            break;  // Too wide already, the remaining columns cannot help
+        }
+    }
+    return total_width;
+}
+
+/**
+ * @struct EmojiLabel
+ * @brief Names of the kind of entries an emoji stands for
+ */
+typedef struct {
+    const char *emoji;    /**< The emoji */
+    const char *singular; /**< Name for one entry */
+    const char *plural;   /**< Name for several entries */
+} EmojiLabel;
+
+static const EmojiLabel emoji_labels[] = {
+    {"📁", "directory", "directories"},
+    {"🔗", "symlink", "symlinks"},
+    {"📸", "image", "images"},
+    {"🎬", "video", "videos"},
+    {"🎧", "audio file", "audio files"},
+    {"📦", "archive", "archives"},
+    {"🗃️", "tarball", "tarballs"},
+    {"🗜️", "compressed file", "compressed files"},
+    {"📄", "document", "documents"},
+    {"📕", "PDF", "PDFs"},
+    {"📝", "text file", "text files"},
+    {"📑", "Markdown file", "Markdown files"},
+    {"💻", "source file", "source files"},
+    {"🔬", "C/C++ source", "C/C++ sources"},
+    {"🧢", "header", "headers"},
+    {"🐍", "Python file", "Python files"},
+    {"🐚", "shell script", "shell scripts"},
+    {"💾", "executable", "executables"},
+    {"⚙️", "config file", "config files"},
+    {"🔧", "device", "devices"},
+    {"❓", "unknown file", "unknown files"},
+};
+
+static const EmojiLabel generic_label = {"", "file", "files"}; // For emojis without a name of their own
+
+/**
+ * @struct EmojiGroup
+ * @brief Entries sharing an emoji, counted for the --summary mode
+ */
+typedef struct {
+    const char *emoji;        /**< The shared emoji, without trailing spaces */
+    size_t emoji_len;         /**< Length of the emoji in bytes */
+    const EmojiLabel *label;  /**< Name of the kind of entries */
+    int count;                /**< Number of entries in the group */
+    int first;                /**< Index of the first entry, to keep the listing order for ties */
+} EmojiGroup;
+
+/**
+ * @brief Find the name of the kind of entries an emoji stands for
+ *
+ * @param emoji The emoji, not necessarily terminated after emoji_len bytes
+ * @param emoji_len Length of the emoji in bytes
+ * @return The matching label, or a generic one for other emojis
+ */
+static const EmojiLabel *find_emoji_label(const char *emoji, size_t emoji_len) {
+    for (size_t i = 0; i < sizeof(emoji_labels) / sizeof(emoji_labels[0]); i++) {
+        if (strlen(emoji_labels[i].emoji) == emoji_len && strncmp(emoji_labels[i].emoji, emoji, emoji_len) == 0) {
+            return &emoji_labels[i];
+        }
+    }
+    return &generic_label;
+}
+
+/**
+ * @brief Compare emoji groups by count, largest first
+ *
+ * @param a Pointer to the first EmojiGroup
+ * @param b Pointer to the second EmojiGroup
+ * @return Negative if a comes first, positive if b does
+ */
+static int compare_emoji_groups(const void *a, const void *b) {
+    const EmojiGroup *group_a = (const EmojiGroup *)a;
+    const EmojiGroup *group_b = (const EmojiGroup *)b;
+    if (group_a->count != group_b->count) {
+        return group_b->count - group_a->count;
+    }
+    return group_a->first - group_b->first;
+}
+
+/**
+ * @brief Print entries grouped by emoji with a count for each group
  *
+ * In plain mode, where the emojis are not shown, entries are grouped by
+ * the name of their kind instead.
+ *
+ * @param out Stream to write to
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param term_width Width of the terminal, groups are wrapped to fit it
+ * @param options Display settings such as plain mode
+ */
+static void write_emoji_groups(FILE *out, const FileCardInfo *entries, int num_entries, int term_width,
+                               const DisplayOptions *options) {
+    EmojiGroup *groups = malloc(num_entries * sizeof(EmojiGroup));
+    if (!groups) {
+        fprintf(stderr, "Memory allocation failed\n");
+        return;
+    }
+
+    int num_groups = 0;
+    for (int i = 0; i < num_entries; i++) {
+        // Directories and symlinks form one group each, whatever their emoji
+        const char *emoji = entries[i].emoji;
+        if (S_ISLNK(entries[i].mode)) {
+            emoji = "🔗";
+        } else if (entries[i].is_directory) {
+            emoji = "📁";
+        }
+        size_t emoji_len = strlen(emoji);
+        while (emoji_len > 0 && emoji[emoji_len - 1] == ' ') emoji_len--;  // Some emojis carry padding
+        const EmojiLabel *label = find_emoji_label(emoji, emoji_len);
+
+        int g = 0;
+        while (g < num_groups &&
+               !(options->plain ? groups[g].label == label
+                                : groups[g].emoji_len == emoji_len && strncmp(groups[g].emoji, emoji, emoji_len) == 0)) {
+            g++;
+        }
+        if (g == num_groups) {
+            groups[num_groups++] = (EmojiGroup){emoji, emoji_len, label, 0, i};
+        }
+        groups[g].count++;
+    }
+
+    qsort(groups, num_groups, sizeof(EmojiGroup), compare_emoji_groups);
+
+    size_t line_width = 0;
+    for (int g = 0; g < num_groups; g++) {
+        const char *kind = groups[g].count == 1 ? groups[g].label->singular : groups[g].label->plural;
+        char item[256];
+        if (options->plain) {
+            snprintf(item, sizeof(item), "%d %s", groups[g].count, kind);
+        } else {
+            snprintf(item, sizeof(item), "%.*s %d %s", (int)groups[g].emoji_len, groups[g].emoji, groups[g].count, kind);
+        }
+
+        // Wrap to a new line instead of letting the terminal break an item
+        size_t item_width = get_display_width(item);
+        if (line_width > 0 && line_width + 2 + item_width + 1 > (size_t)term_width) {
+            fprintf(out, ",\n");
+            line_width = 0;
+        } else if (line_width > 0) {
+            fprintf(out, ", ");
+            line_width += 2;
+        }
+        fprintf(out, "%s", item);
+        line_width += item_width;
+    }
+    fprintf(out, "\n");
+
+    free(groups);
+}
+
+/**
+ * @brief Return an ANSI escape code if the options ask for color
+ *
+ * @param code Escape sequence to print
+ * @param options Display settings holding the color flag
+ * @return code when color is on, an empty string otherwise
+ */
+static const char *style(const char *code, const DisplayOptions *options) {
+    return options->color ? code : "";
+}
+
+/**
+ * @brief Write a single entry with its emoji, color and Git marker
+ *
+ * @param out Stream to write to
+ * @param entry The entry to display
+ * @param name_length Number of bytes of the display name to print
+ * @param options Display settings such as plain mode
+ */
+void write_entry(FILE *out, const FileCardInfo *entry, size_t name_length, const DisplayOptions *options) {
+    if (!options->plain) {
+        fprintf(out, "%s ", entry->emoji);  // Print emoji
+    }
+
+    // Print name in its LS_COLORS color, with an ellipsis if it had to be cut
+    const char *name = entry->display_name;
+    const char *color = options->color ? find_ls_color(entry->name, entry->mode, entry->target_mode) : NULL;
+    if (color) {
+        fprintf(out, "\033[%sm", color);
+    }
+    fprintf(out, "%.*s%s", (int)name_length, name, name[name_length] ? "…" : "");
+    if (color) {
+        fprintf(out, "%s", ANSI_RESET);
+    }
+
+    // Print Git status if applicable
+    if (options->hide_git_status) {
+        // Markers are left out, e.g. when output is piped
+    } else if (entry->git_status[0] != '\0') {
+        // Staged changes are green, work tree changes orange
+        const char *git_color = entry->git_staged ? ANSI_GIT_STAGED : ANSI_GIT_STATUS;
+        fprintf(out, "%s[%c]%s", style(git_color, options), entry->git_status[0], style(ANSI_RESET, options));
+    } else if (entry->is_directory && entry->contains_modified) {
+        fprintf(out, "%s[+]%s", style(ANSI_GIT_STATUS, options), style(ANSI_RESET, options));
+    }
+}
+
+/**
+ * @brief Write directory entries in a grid layout
+ *
+ * @param out Stream to write to
  * @param entries Array of FileCardInfo structures
  * @param num_entries Number of entries in the array
  * @param term_width Width of the terminal
  * @param current_dir Current directory path
  * @param show_path Flag to determine if the path should be displayed
+ * @param options Display settings such as the column limit
  */
-void display_entries(FileCardInfo *entries, int num_entries, int term_width,
-                     const char *current_dir, int show_path) {
-    setlocale(LC_ALL, "");
-
+void write_entries(FILE *out, FileCardInfo *entries, int num_entries, int term_width,
+                   const char *current_dir, int show_path, const DisplayOptions *options) {
     if (show_path) {
-        char *branch = get_current_branch(current_dir);
+        // Only start git inside a repository, like integrate_git_status
+        char *branch = is_git_repository(current_dir) ? get_current_branch(current_dir) : NULL;
         if (branch) {
-            printf("\033[1m%s\033[0m  \033[38;5;202m[%s]\033[0m\n", current_dir, branch);  // Print directory and branch
+            fprintf(out, "%s%s%s  %s[%s]%s\n", style(ANSI_BOLD, options), current_dir, style(ANSI_RESET, options),
+                    style(ANSI_GIT_STATUS, options), branch, style(ANSI_RESET, options));  // Print directory and branch
             free(branch);  // Free the allocated branch name
         } else {
-            printf("\033[1m%s\033[0m\n", current_dir);  // Print directory without branch
+            fprintf(out, "%s%s%s\n", style(ANSI_BOLD, options), current_dir, style(ANSI_RESET, options));  // Print directory without branch
         }
     }
 
-    // Allocate memory for entry widths
+    // Print only counts per emoji in --summary mode
+    if (options->group_by_emoji) {
+        write_emoji_groups(out, entries, num_entries, term_width, options);
+        return;
+    }
+
+    // Allocate memory for entry widths and the number of name bytes to print
     size_t *entry_widths = malloc(num_entries * sizeof(size_t));
-    if (!entry_widths) {
+    size_t *name_lengths = malloc(num_entries * sizeof(size_t));
+    if (!entry_widths || !name_lengths) {
         fprintf(stderr, "Memory allocation failed\n");
+        free(entry_widths);
+        free(name_lengths);
         return;
     }
 
-    size_t max_width = 0;  // Track the maximum entry width
+    size_t min_entry_width = SIZE_MAX;
     for (int i = 0; i < num_entries; i++) {
-        // Calculate the width of each entry
-        entry_widths[i] = EMOJI_WIDTH + EMOJI_NAME_SPACING + get_display_width(entries[i].name);
-        if (entries[i].git_status[0] != '\0' || (entries[i].is_directory && entries[i].contains_modified)) {
-            entry_widths[i] += 3;  // Add space for Git status
+        // Calculate the width of each entry, leaving out the emoji in plain mode
+        size_t name_width = get_display_width(entries[i].display_name);
+        size_t decoration_width = 0;
+        if (!options->plain) {
+            // Link emojis such as "🔗📁" are two glyphs wide
+            decoration_width += get_display_width(entries[i].emoji) + EMOJI_NAME_SPACING;
         }
-        if (entry_widths[i] > max_width) {
-            max_width = entry_widths[i];  // Update maximum width
+        if (!options->hide_git_status &&
+            (entries[i].git_status[0] != '\0' || (entries[i].is_directory && entries[i].contains_modified))) {
+            decoration_width += 3;  // Add space for Git status
         }
+        name_lengths[i] = strlen(entries[i].display_name);
+
+        // Cut names that would wrap even in a single column, leaving room for the ellipsis
+        if (options->truncate_names && decoration_width + name_width > (size_t)term_width) {
+            size_t available = (size_t)term_width > decoration_width + 1 ? term_width - decoration_width - 1 : 0;
+            name_lengths[i] = fit_to_width(entries[i].display_name, available, &name_width);
+            name_width += 1;  // The ellipsis
+        }
+        entry_widths[i] = decoration_width + name_width;
+        if (entry_widths[i] < min_entry_width) min_entry_width = entry_widths[i];
     }
 
-    // Calculate the number of columns based on terminal width
-    int num_columns = (term_width + SPACING) / (max_width + SPACING);
-    int max_columns = get_max_columns(current_dir);  // Get max columns for the current directory
-    if (num_columns > max_columns) num_columns = max_columns;
-    if (num_columns < 1) num_columns = 1;
+    // No more columns fit than there are narrowest entries side by side
+    size_t fitting_columns = ((size_t)term_width + SPACING) / (min_entry_width + SPACING);
+    int max_columns = fitting_columns < (size_t)num_entries ? (int)fitting_columns : num_entries;
+
+    // Apply the requested column limit, or the one configured for the current directory
+    int column_limit = options->max_columns > 0 ? options->max_columns : get_max_columns(current_dir);
+    if (column_limit > 0 && max_columns > column_limit) max_columns = column_limit;
+    if (max_columns < 1) max_columns = 1;
 
     // Allocate memory for column widths
-    size_t *column_widths = calloc(num_columns, sizeof(size_t));
+    size_t *column_widths = calloc(max_columns, sizeof(size_t));
     if (!column_widths) {
         fprintf(stderr, "Memory allocation failed\n");
         free(entry_widths);
+        free(name_lengths);
         return;
     }
 
-    int rows = (num_entries + num_columns - 1) / num_columns;  // Calculate number of rows
-
-    // Determine the width of each column
-    for (int i = 0; i < num_entries; i++) {
-        int col = i / rows;
-        if (entry_widths[i] > column_widths[col]) {
-            column_widths[col] = entry_widths[i];
-        }
+    // Search from the widest layout down for the first one that fits the terminal;
+    // a single column is always accepted, even if an entry is wider than the terminal
+    int num_columns = max_columns;
+    while (num_columns > 1 &&
+           calculate_column_widths(entry_widths, num_entries, num_columns, options->across, column_widths,
+                                   (size_t)term_width) > (size_t)term_width) {
+        num_columns--;
     }
+    calculate_column_widths(entry_widths, num_entries, num_columns, options->across, column_widths, SIZE_MAX);
+
+    int rows = grid_rows(num_entries, num_columns, options->across);
 
-    // Display entries in a grid
+    // Display entries in a grid, filling columns top to bottom or rows left to right
     for (int row = 0; row < rows; row++) {
         for (int col = 0; col < num_columns; col++) {
-            int index = col * rows + row;
-            if (index < num_entries) {
-                printf("%s ", entries[index].emoji);  // Print emoji
-                printf("%s", entries[index].name);    // Print name
-
-                // Print Git status if applicable
-                if (entries[index].git_status[0] != '\0') {
-                    printf("\033[38;5;202m[%c]\033[0m", entries[index].git_status[0]);
-                } else if (entries[index].is_directory && entries[index].contains_modified) {
-                    printf("\033[38;5;202m[+]\033[0m");
-                }
+            int index = grid_index(row, col, num_entries, num_columns, options->across);
+            if (index >= 0) {
+                write_entry(out, &entries[index], name_lengths[index], options);
 
-                // Add padding to align columns
+                // Add padding to align columns, unless nothing follows on this row
                 size_t printed_width = entry_widths[index];
-                size_t padding = (col < num_columns - 1) ? column_widths[col] - printed_width + SPACING : 0;
-                for (size_t i = 0; i < padding; i++) {
-                    printf(" ");
+                int has_next = grid_index(row, col + 1, num_entries, num_columns, options->across) >= 0;
+                size_t padding = 0;
+                if (has_next) {
+                    // Never let the subtraction wrap around if the widths ever disagree
+                    padding = (column_widths[col] > printed_width ? column_widths[col] - printed_width : 0) + SPACING;
                 }
+                fprintf(out, "%*s", (int)padding, "");
             }
         }
-        printf("\n");  // Move to the next row
+        fprintf(out, "\n");  // Move to the next row
     }
 
     free(entry_widths);    // Free allocated memory
+    free(name_lengths);    // Free allocated memory
     free(column_widths);   // Free allocated memory
 }
+
+/**
+ * @brief Write directory entries in a grid layout into a memory buffer
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param term_width Width to lay the grid out for
+ * @param current_dir Directory the entries belong to
+ * @param show_path Flag to determine if the path should be written
+ * @param options Display settings such as the column limit
+ * @param size Set to the length of the written text
+ * @return A dynamically allocated string, or NULL on failure
+ */
+static char *render_to_buffer(FileCardInfo *entries, int num_entries, int term_width, const char *current_dir,
+                              int show_path, const DisplayOptions *options, size_t *size) {
+    char *buffer = NULL;
+    FILE *out = open_memstream(&buffer, size);
+    if (!out) {
+        return NULL;
+    }
+
+    write_entries(out, entries, num_entries, term_width, current_dir, show_path, options);
+
+    if (fclose(out) != 0) {
+        free(buffer);
+        return NULL;
+    }
+    return buffer;
+}
+
+/**
+ * @brief Display directory entries in a grid layout on standard output
+ *
+ * The grid is built in memory and written with a single call, instead of
+ * one write per row when stdout is a terminal.
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param term_width Width of the terminal
+ * @param current_dir Current directory path
+ * @param show_path Flag to determine if the path should be displayed
+ * @param options Display settings such as the column limit
+ */
+void display_entries(FileCardInfo *entries, int num_entries, int term_width,
+                     const char *current_dir, int show_path, const DisplayOptions *options) {
+    size_t size = 0;
+    char *buffer = render_to_buffer(entries, num_entries, term_width, current_dir, show_path, options, &size);
+    if (!buffer) {
+        // Fall back to writing directly if the buffer could not be allocated
+        write_entries(stdout, entries, num_entries, term_width, current_dir, show_path, options);
+        return;
+    }
+
+    fwrite(buffer, 1, size, stdout);
+    free(buffer);
+}
+
+/**
+ * @brief Render directory entries in a grid layout into a string
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param term_width Width to lay the grid out for
+ * @param current_dir Directory the entries belong to
+ * @param options Display settings such as the column limit
+ * @return A dynamically allocated string, or NULL on failure.
+ *         The caller is responsible for freeing this memory.
+ */
+char *render_entries(FileCardInfo *entries, int num_entries, int term_width,
+                     const char *current_dir, const DisplayOptions *options) {
+    size_t size = 0;
+    return render_to_buffer(entries, num_entries, term_width, current_dir, 0, options, &size);
+}
+
+/**
+ * @brief Formats a file size into a human-readable string.
+ *
+ * @param size The size in bytes to format.
+ * @return A pointer to a static buffer containing the formatted size string.
+ */
+char *format_size(off_t size) {
+    static char buf[64];
+    const char *units[] = {"B", "K", "M", "G", "T", "P", "E", "Z", "Y"};
+    int i = 0;
+    double dsize = size;
+
+    while (dsize >= 1024 && i < 8) {
+        dsize /= 1024;
+        i++;
+    }
+
+    snprintf(buf, sizeof(buf), "%.1f%s", dsize, units[i]);
+    return buf;
+}
+
+/**
+ * @brief Print a summary line with entry counts and total file size
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param options Display settings; emojis are left out in plain mode
+ */
+void display_summary(const FileCardInfo *entries, int num_entries, const DisplayOptions *options) {
+    int dirs = 0, files = 0, links = 0, others = 0;
+    off_t total_size = 0;
+
+    for (int i = 0; i < num_entries; i++) {
+        if (S_ISLNK(entries[i].mode)) {
+            links++;  // Links are counted apart from what they point at
+        } else if (S_ISDIR(entries[i].mode)) {
+            dirs++;
+        } else if (S_ISREG(entries[i].mode)) {
+            files++;
+            total_size += entries[i].size;  // Only regular files have a meaningful size
+        } else {
+            others++;  // Devices, sockets and pipes
+        }
+    }
+
+    int plain = options->plain;
+    printf("\n%s%d %s  %s%d %s  %s%d %s", plain ? "" : "📁 ", dirs, dirs == 1 ? "directory" : "directories",
+           plain ? "" : "📄 ", files, files == 1 ? "file" : "files",
+           plain ? "" : "🔗 ", links, links == 1 ? "symlink" : "symlinks");
+    if (others > 0) {
+        printf("  %s%d other", plain ? "" : "🔧 ", others);
+    }
+    printf("  %s%s\n", plain ? "" : "🧮 ", format_size(total_size));
+}
+
+/**
+ * @brief Print a string as a JSON string literal
+ *
+ * Control characters are written as \uXXXX escapes, so the literal
+ * decodes to exactly the bytes given.
+ *
+ * @param str The string to print, assumed to be valid UTF-8
+ * @param length Number of bytes of the string to print
+ */
+static void print_json_string(const char *str, size_t length) {
+    putchar('"');
+    for (const unsigned char *p = (const unsigned char *)str; p < (const unsigned char *)str + length; p++) {
+        if (*p == '"' || *p == '\\') {
+            printf("\\%c", *p);  // Escape quotes and backslashes
+        } else if (*p < 0x20) {
+            printf("\\u%04x", *p);  // Escape control characters
+        } else {
+            putchar(*p);
+        }
+    }
+    putchar('"');
+}
+
+/**
+ * @brief Print entries as a JSON array of objects
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ */
+void display_json(const FileCardInfo *entries, int num_entries) {
+    printf("[");
+    for (int i = 0; i < num_entries; i++) {
+        // Names that are not valid UTF-8 cannot be JSON strings, use their printable form
+        int name_escaped = !is_valid_utf8(entries[i].name);
+        const char *name = name_escaped ? entries[i].display_name : entries[i].name;
+        printf("%s\n  {\"name\": ", i > 0 ? "," : "");
+        print_json_string(name, strlen(name));
+        printf(", \"name_escaped\": %s, \"emoji\": ", name_escaped ? "true" : "false");
+
+        // Leave out the padding some emojis carry for the grid
+        size_t emoji_len = strlen(entries[i].emoji);
+        while (emoji_len > 0 && entries[i].emoji[emoji_len - 1] == ' ') emoji_len--;
+        print_json_string(entries[i].emoji, emoji_len);
+        printf(", \"is_directory\": %s, \"is_hidden\": %s, \"size\": %lld, \"modified\": %lld}",
+               entries[i].is_directory ? "true" : "false",
+               entries[i].is_hidden ? "true" : "false",
+               (long long)entries[i].size, (long long)entries[i].mtime);
+    }
+    printf("%s]\n", num_entries > 0 ? "\n" : "");
+}
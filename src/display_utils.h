@@ -1,9 +1,216 @@
 #ifndef DISPLAY_UTILS_H
 #define DISPLAY_UTILS_H
 
+#include <stdio.h>
+
 #include "file_card.h"
 
+// ANSI escape codes for text formatting
+#define ANSI_BOLD "\033[1m"
+#define ANSI_RESET "\033[0m"
+#define ANSI_GIT_STATUS "\033[38;5;202m"
+#define ANSI_GIT_STAGED "\033[32m"
+
+/**
+ * @struct DisplayOptions
+ * @brief Settings that control how the grid of entries is rendered.
+ */
+typedef struct {
+    int max_columns;  /**< Upper bound on grid columns, 0 for the directory's own limit or as many as fit */
+    int plain;        /**< Print names without emojis (1) or with them (0) */
+    int hide_git_status; /**< Leave out Git status markers (1) or show them (0) */
+    int across;          /**< Fill rows left to right (1) instead of columns top to bottom (0) */
+    int group_by_emoji;  /**< Print counts per emoji (1) instead of the entries themselves (0) */
+    int truncate_names;  /**< Cut names wider than the terminal with an ellipsis (1) or print them in full (0) */
+    int color;           /**< Print LS_COLORS colors and ANSI styles (1) or plain text (0) */
+} DisplayOptions;
+
+/**
+ * @brief Check whether ANSI styling is enabled
+ *
+ * Styling is disabled when the NO_COLOR environment variable is set to a
+ * non-empty value or when standard output is not a terminal.
+ *
+ * @return 1 if escape codes should be printed, 0 otherwise
+ */
+int ansi_enabled(void);
+
+/**
+ * @brief Return an ANSI escape code if styling is enabled
+ *
+ * Styling is disabled when the NO_COLOR environment variable is set to a
+ * non-empty value or when standard output is not a terminal.
+ *
+ * @param code The escape code to emit, e.g. ANSI_BOLD
+ * @return The code itself, or an empty string when styling is disabled
+ */
+const char *ansi_style(const char *code);
+
+/**
+ * @brief Calculate the display width of the character at the start of a string
+ *
+ * Following zero-width characters and characters joined with a zero-width
+ * joiner are counted as part of the character. get_display_width and
+ * fit_to_width are both built on this, so they always agree.
+ *
+ * @param str Pointer to the start of a UTF-8 character, not the terminator
+ * @param length Output for the number of bytes taken together
+ * @return Display width of the character in columns
+ */
+size_t char_display_width(const char *str, size_t *length);
+
+/**
+ * @brief Calculate the display width of a UTF-8 string
+ *
+ * Wide characters and emoji take two columns, while combining marks,
+ * variation selectors and characters joined with a zero-width joiner
+ * take none.
+ *
+ * @param str The UTF-8 string to measure
+ * @return Display width of the string in terminal columns
+ */
+size_t get_display_width(const char *str);
+
+/**
+ * @brief Find the longest prefix of a UTF-8 string that fits a display width
+ *
+ * Characters are never split, and zero-width characters stay with the
+ * character they belong to.
+ *
+ * @param str The UTF-8 string to measure
+ * @param max_width The available display width
+ * @param prefix_width Output for the display width of the prefix
+ * @return Length of the prefix in bytes
+ */
+size_t fit_to_width(const char *str, size_t max_width, size_t *prefix_width);
+
+/**
+ * @brief Get the index of the first entry in a column
+ *
+ * Entries are spread as evenly as possible: the first
+ * (num_entries % num_columns) columns hold one entry more than the rest.
+ *
+ * @param col The column to look up
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @return Index of the first entry in the column
+ */
+int column_start(int col, int num_entries, int num_columns);
+
+/**
+ * @brief Get the number of entries in a column
+ *
+ * @param col The column to look up
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @return Number of entries in the column
+ */
+int column_length(int col, int num_entries, int num_columns);
+
+/**
+ * @brief Get the number of rows of a grid
+ *
+ * @param num_entries Number of entries
+ * @param num_columns Number of columns in the grid
+ * @param across Whether entries fill rows left to right instead of columns
+ * @return Number of rows in the grid
+ */
+int grid_rows(int num_entries, int num_columns, int across);
+
+/**
+ * @brief Write a single entry with its emoji, color and Git marker
+ *
+ * No padding or newline is printed after the entry.
+ *
+ * @param out Stream to write to
+ * @param entry The entry to display
+ * @param name_length Number of bytes of the display name to print; a
+ *                    shorter length than the name cuts it with an ellipsis
+ * @param options Display settings such as plain mode
+ */
+void write_entry(FILE *out, const FileCardInfo *entry, size_t name_length, const DisplayOptions *options);
+
+/**
+ * @brief Write directory entries in a grid layout
+ *
+ * The grid uses as many columns as fit in term_width, up to the column
+ * limit in the options or the default for current_dir.
+ *
+ * @param out Stream to write to
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param term_width Width of the terminal
+ * @param current_dir Directory the entries belong to
+ * @param show_path Print current_dir and its Git branch above the grid
+ * @param options Display settings such as the column limit
+ */
+void write_entries(FILE *out, FileCardInfo *entries, int num_entries, int term_width,
+                   const char *current_dir, int show_path, const DisplayOptions *options);
+
+/**
+ * @brief Display directory entries in a grid layout on standard output
+ *
+ * Same as write_entries with stdout as the stream. The whole grid is
+ * built in memory first and written to stdout at once.
+ */
 void display_entries(FileCardInfo *entries, int num_entries, int term_width,
-                     const char *current_dir, int show_path);
+                     const char *current_dir, int show_path, const DisplayOptions *options);
+
+/**
+ * @brief Render directory entries in a grid layout into a string
+ *
+ * This is the counterpart of list_directory for programs that use facad
+ * as a library and want the listing as text rather than on stdout.
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param term_width Width to lay the grid out for
+ * @param current_dir Directory the entries belong to
+ * @param options Display settings such as the column limit
+ * @return A dynamically allocated string, or NULL on failure.
+ *         The caller is responsible for freeing this memory.
+ */
+char *render_entries(FileCardInfo *entries, int num_entries, int term_width,
+                     const char *current_dir, const DisplayOptions *options);
+
+/**
+ * @brief Format a file size into a human-readable string
+ *
+ * Sizes are shown with one decimal in binary units, e.g. "1.5K".
+ *
+ * @param size The size in bytes to format
+ * @return A pointer to a static buffer, overwritten by the next call
+ */
+char *format_size(off_t size);
+
+/**
+ * @brief Print a summary line with entry counts and total file size
+ *
+ * Symbolic links are counted on their own, regardless of their target,
+ * and only regular files contribute to the total size.
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ * @param options Display settings; emojis are left out in plain mode
+ */
+void display_summary(const FileCardInfo *entries, int num_entries, const DisplayOptions *options);
+
+/**
+ * @brief Print entries as a JSON array of objects
+ *
+ * Each object has the fields name, name_escaped, emoji, is_directory,
+ * is_hidden, size (in bytes, as reported by lstat) and modified (seconds
+ * since the Unix epoch). No ANSI styling or column layout is applied.
+ *
+ * A name that is valid UTF-8 is written as it is, with only JSON
+ * escaping, and name_escaped is false. Other names are written in
+ * their printable form from make_display_name, with invalid and control
+ * bytes as \xNN and literal backslashes doubled, and name_escaped is
+ * true. The emoji has no trailing padding.
+ *
+ * @param entries Array of FileCardInfo structures
+ * @param num_entries Number of entries in the array
+ */
+void display_json(const FileCardInfo *entries, int num_entries);
 
 #endif // DISPLAY_UTILS_H
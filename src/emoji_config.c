@@ -0,0 +1,307 @@
+/**
+ * @file emoji_config.c
+ * @brief Implementation of user-defined emoji mappings.
+ *
+ * This file contains a small parser for the emoji configuration file
+ * and lookup functions for the mappings it defines. Only the subset of
+ * TOML needed for the file is supported: [extensions] and [files] tables
+ * with `key = "emoji"` lines and # comments.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#include <ctype.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <strings.h>
+
+#include "emoji_config.h"
+
+#define MAX_PATH 4096
+#define MAX_LINE 1024
+
+/**
+ * @struct UserEmojiEntry
+ * @brief A single user mapping with owned key and emoji strings.
+ */
+typedef struct {
+    char *key;   /**< File name or extension */
+    char *emoji; /**< The corresponding emoji */
+} UserEmojiEntry;
+
+/**
+ * @struct UserEmojiMap
+ * @brief Growable array of user mappings.
+ */
+typedef struct {
+    UserEmojiEntry *entries; /**< Array of mappings */
+    size_t count;            /**< Number of mappings in the array */
+    size_t size;             /**< Allocated capacity of the array */
+} UserEmojiMap;
+
+static UserEmojiMap user_extension_map; // Mappings from the [extensions] table
+static UserEmojiMap user_file_map;      // Mappings from the [files] table
+
+/**
+ * @brief Adds a mapping, replacing an earlier one with the same key.
+ *
+ * @param map The map to add to.
+ * @param key The key, ownership is taken.
+ * @param emoji The emoji, ownership is taken.
+ */
+static void add_mapping(UserEmojiMap *map, char *key, char *emoji) {
+    for (size_t i = 0; i < map->count; i++) {
+        if (strcasecmp(map->entries[i].key, key) == 0) {
+            free(map->entries[i].emoji);
+            map->entries[i].emoji = emoji;
+            free(key);
+            return;
+        }
+    }
+
+    if (map->count >= map->size) {
+        size_t new_size = map->size ? map->size * 2 : 16;
+        UserEmojiEntry *new_entries = realloc(map->entries, new_size * sizeof(UserEmojiEntry));
+        if (!new_entries) {
+            free(key);
+            free(emoji);
+            return;
+        }
+        map->entries = new_entries;
+        map->size = new_size;
+    }
+
+    map->entries[map->count].key = key;
+    map->entries[map->count].emoji = emoji;
+    map->count++;
+}
+
+/**
+ * @brief Skips spaces and tabs.
+ *
+ * @param p Pointer into the line.
+ * @return Pointer to the first character that is not a space or tab.
+ */
+static char *skip_blanks(char *p) {
+    while (*p == ' ' || *p == '\t') p++;
+    return p;
+}
+
+/**
+ * @brief Parses a quoted string ("basic" with escapes, or 'literal').
+ *
+ * @param cursor Pointer to the current position, advanced past the string.
+ * @return A newly allocated copy of the string contents, or NULL if malformed.
+ */
+static char *parse_quoted(char **cursor) {
+    char *p = *cursor;
+    char quote = *p;
+    if (quote != '"' && quote != '\'') return NULL;
+    p++;
+
+    char *result = malloc(strlen(p) + 1);
+    if (!result) return NULL;
+
+    char *out = result;
+    while (*p && *p != quote) {
+        // Basic strings allow escaping quotes and backslashes
+        if (quote == '"' && *p == '\\' && (p[1] == '"' || p[1] == '\\')) p++;
+        *out++ = *p++;
+    }
+    if (*p != quote) {
+        free(result);
+        return NULL;  // Unterminated string
+    }
+
+    *out = '\0';
+    *cursor = p + 1;
+    return result;
+}
+
+/**
+ * @brief Parses a key, either bare (letters, digits, '_', '-', '.') or quoted.
+ *
+ * @param cursor Pointer to the current position, advanced past the key.
+ * @return A newly allocated copy of the key, or NULL if malformed.
+ */
+static char *parse_key(char **cursor) {
+    char *p = *cursor;
+    if (*p == '"' || *p == '\'') return parse_quoted(cursor);
+
+    char *start = p;
+    while (isalnum((unsigned char)*p) || *p == '_' || *p == '-' || *p == '.') p++;
+    if (p == start) return NULL;
+
+    size_t len = p - start;
+    char *key = malloc(len + 1);
+    if (!key) return NULL;
+    memcpy(key, start, len);
+    key[len] = '\0';
+    *cursor = p;
+    return key;
+}
+
+/**
+ * @brief Parses a `key = "emoji"` line and adds it to the map.
+ *
+ * @param line The line to parse, without the trailing newline.
+ * @param map The map of the current table, or NULL to discard the mapping.
+ * @return 0 on success, -1 if the line is malformed.
+ */
+static int parse_mapping(char *line, UserEmojiMap *map) {
+    char *p = line;
+    char *key = parse_key(&p);
+    if (!key) return -1;
+
+    p = skip_blanks(p);
+    if (*p != '=') {
+        free(key);
+        return -1;
+    }
+    p = skip_blanks(p + 1);
+
+    char *emoji = parse_quoted(&p);
+    p = skip_blanks(p);
+    if (!emoji || (*p != '\0' && *p != '#') || key[0] == '\0' || emoji[0] == '\0') {
+        free(key);
+        free(emoji);
+        return -1;
+    }
+
+    if (!map) {
+        free(key);
+        free(emoji);
+        return 0;
+    }
+
+    // Allow extensions to be written with a leading dot
+    if (map == &user_extension_map && key[0] == '.') {
+        memmove(key, key + 1, strlen(key));
+    }
+
+    add_mapping(map, key, emoji);
+    return 0;
+}
+
+/**
+ * @brief Loads user emoji mappings from the configuration file.
+ */
+void load_emoji_config(void) {
+    char config_path[MAX_PATH];
+    const char *config_home = getenv("XDG_CONFIG_HOME");
+    const char *home = getenv("HOME");
+
+    if (config_home && config_home[0] != '\0') {
+        snprintf(config_path, sizeof(config_path), "%s/facad/emoji.toml", config_home);
+    } else if (home && home[0] != '\0') {
+        snprintf(config_path, sizeof(config_path), "%s/.config/facad/emoji.toml", home);
+    } else {
+        return;  // Nowhere to look for the configuration
+    }
+
+    FILE *file = fopen(config_path, "r");
+    if (!file) return;  // The configuration file is optional
+
+    char line[MAX_LINE];
+    int line_number = 0;
+    UserEmojiMap *current_map = NULL;  // Mappings before any table are ignored
+
+    while (fgets(line, sizeof(line), file)) {
+        line_number++;
+        line[strcspn(line, "\r\n")] = '\0';
+
+        char *p = skip_blanks(line);
+        if (*p == '\0' || *p == '#') continue;
+
+        if (*p == '[') {
+            // Table header selecting which map the following lines go to
+            char *end = strchr(p, ']');
+            if (!end) {
+                fprintf(stderr, "facad: %s:%d: malformed table header\n", config_path, line_number);
+                current_map = NULL;
+                continue;
+            }
+            *end = '\0';
+            char *name = skip_blanks(p + 1);
+            name[strcspn(name, " \t")] = '\0';
+
+            if (strcmp(name, "extensions") == 0) {
+                current_map = &user_extension_map;
+            } else if (strcmp(name, "files") == 0) {
+                current_map = &user_file_map;
+            } else {
+                fprintf(stderr, "facad: %s:%d: unknown table '%s'\n", config_path, line_number, name);
+                current_map = NULL;
+            }
+            continue;
+        }
+
+        if (parse_mapping(p, current_map) != 0) {
+            fprintf(stderr, "facad: %s:%d: ignoring malformed line\n", config_path, line_number);
+        }
+    }
+
+    fclose(file);
+}
+
+/**
+ * @brief Looks up a key in a user map.
+ *
+ * @param map The map to search.
+ * @param key The key to look up (compared case-insensitively).
+ * @return The configured emoji, or NULL if there is no mapping.
+ */
+static const char *find_mapping(const UserEmojiMap *map, const char *key) {
+    for (size_t i = 0; i < map->count; i++) {
+        if (strcasecmp(map->entries[i].key, key) == 0) {
+            return map->entries[i].emoji;
+        }
+    }
+    return NULL;
+}
+
+/**
+ * @brief Looks up a user-defined emoji for an exact file name.
+ *
+ * @param filename The file name to look up.
+ * @return The configured emoji, or NULL if there is no mapping.
+ */
+const char *find_user_file_emoji(const char *filename) {
+    return find_mapping(&user_file_map, filename);
+}
+
+/**
+ * @brief Looks up a user-defined emoji for a file extension.
+ *
+ * @param extension The extension without the leading dot.
+ * @return The configured emoji, or NULL if there is no mapping.
+ */
+const char *find_user_extension_emoji(const char *extension) {
+    return find_mapping(&user_extension_map, extension);
+}
+
+/**
+ * @brief Frees the entries of a user map and resets it.
+ *
+ * @param map The map to free.
+ */
+static void free_mapping(UserEmojiMap *map) {
+    for (size_t i = 0; i < map->count; i++) {
+        free(map->entries[i].key);
+        free(map->entries[i].emoji);
+    }
+    free(map->entries);
+    map->entries = NULL;
+    map->count = 0;
+    map->size = 0;
+}
+
+/**
+ * @brief Frees all loaded user emoji mappings.
+ */
+void free_emoji_config(void) {
+    free_mapping(&user_extension_map);
+    free_mapping(&user_file_map);
+}
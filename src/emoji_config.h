@@ -0,0 +1,67 @@
+/**
+ * @file emoji_config.h
+ * @brief User-defined emoji mappings loaded from a configuration file.
+ *
+ * This header file declares functions for loading the optional emoji
+ * configuration file (~/.config/facad/emoji.toml) and looking up the
+ * mappings it defines. User mappings take priority over the built-in
+ * tables in emoji_mappings.c.
+ *
+ * The file uses a small subset of TOML:
+ *
+ * @code
+ * [extensions]
+ * rs = "🦀"
+ * lua = "🌙"
+ * "tar.gz" = "🗃️"
+ *
+ * [files]
+ * "Justfile" = "🤖"
+ * @endcode
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#ifndef EMOJI_CONFIG_H
+#define EMOJI_CONFIG_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/**
+ * @brief Loads user emoji mappings from the configuration file.
+ *
+ * The file is looked up as $XDG_CONFIG_HOME/facad/emoji.toml, falling back
+ * to ~/.config/facad/emoji.toml. A missing file is silently ignored, and
+ * malformed lines are skipped with a warning on stderr.
+ */
+void load_emoji_config(void);
+
+/**
+ * @brief Looks up a user-defined emoji for an exact file name.
+ *
+ * @param filename The file name to look up (compared case-insensitively).
+ * @return The configured emoji, or NULL if there is no mapping.
+ */
+const char *find_user_file_emoji(const char *filename);
+
+/**
+ * @brief Looks up a user-defined emoji for a file extension.
+ *
+ * @param extension The extension without the leading dot (compared case-insensitively).
+ * @return The configured emoji, or NULL if there is no mapping.
+ */
+const char *find_user_extension_emoji(const char *extension);
+
+/**
+ * @brief Frees all loaded user emoji mappings.
+ */
+void free_emoji_config(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* EMOJI_CONFIG_H */
@@ -43,9 +43,18 @@ const EmojiMapEntry emoji_extension_map[] = {
     {"aac", "🎧"},
     {"zip", "📦"},
     {"tar", "📦"},
-    {"gz", "📦"},
-    {"bz2", "📦"},
-    {"xz", "📦"},
+    {"tar.gz", "🗃️ "},
+    {"tgz", "🗃️ "},
+    {"tar.bz2", "🗃️ "},
+    {"tbz2", "🗃️ "},
+    {"tar.xz", "🗃️ "},
+    {"txz", "🗃️ "},
+    {"tar.zst", "🗃️ "},
+    {"tar.lz", "🗃️ "},
+    {"tar.lzma", "🗃️ "},
+    {"gz", "🗜️ "},
+    {"bz2", "🗜️ "},
+    {"xz", "🗜️ "},
     {"7z", "📦"},
     {"rar", "📦"},
     {"xip", "📦"},
@@ -315,6 +324,9 @@ const EmojiMapEntry emoji_exact_file_map[] = {
     {"WORKSPACE", "🏗️ "},
     {".bazelrc", "🏗️ "},
     {"Makefile.in", "🏗️ "},
+    {"Makefile", "🔨"},
+    {"GNUmakefile", "🔨"},
+    {"Justfile", "🔨"},
     {"configure.ac", "🏗️ "},
     // {".gitignore", "🙈"},
     // {".dockerignore", "🙈"},
@@ -332,13 +344,18 @@ const EmojiMapEntry emoji_exact_file_map[] = {
     // {".yapfignore", "🙈"},
     {"Dockerfile", "🐳"},
     {"Dockerfile.txt", "🐳"},
+    {"Containerfile", "🐳"},
+    {"docker-compose.yml", "🐳"},
+    {"docker-compose.yaml", "🐳"},
+    {"compose.yaml", "🐳"},
     {".gitlab-ci.yml", "🦊"},
     {".travis.yml", "⛑️"},
     {"swagger.yaml", "🧣"},
     {"Jenkinsfile", "🔴"},
     {"tags", "🏷️ "},
     {".hgtags", "🏷️ "},
-    //{"LICENSE", "⚖️ "},
+    {"LICENSE", "⚖️ "},
+    {"LICENCE", "⚖️ "},
     //{"LICENSE.TXT", "⚖️ "},
     //{"LICENSE.txt", "⚖️ "},
     {"COPYING", "⚖️ "},
@@ -448,6 +465,10 @@ const EmojiMapEntry emoji_exact_file_map[] = {
     {".flowconfig", "🧹"},
     {"Doxyfile", "🕮 "},
     {"AUTHORS", "📝"},
+    {"README", "📖"},
+    {"CHANGELOG", "📜"},
+    {"CHANGES", "📜"},
+    {"NEWS", "📜"},
     {"index", "📌"},
     {"perf.data", "⏱️"},
     {"perf.data.old", "⏱️"},
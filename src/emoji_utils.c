@@ -17,6 +17,7 @@
 #include <sys/stat.h>
 
 #include "emoji_utils.h"
+#include "emoji_config.h"
 #include "emoji_mappings.h"
 
 #define MAX_PATH 4096
@@ -67,51 +68,172 @@ char *get_dev_emoji(const char *path) {
 }
 
 /**
- * @brief Checks the file content for specific patterns to determine the file type
+ * @brief Reads the beginning of a file for content checks
  *
  * @param path The path to the file
+ * @param buffer Buffer to fill with the first bytes of the file
+ * @param size Size of the buffer
+ * @return Number of bytes read, or -1 if the file cannot be opened
+ */
+static long read_file_head(const char *path, unsigned char *buffer, size_t size) {
+    FILE *file = fopen(path, "rb");
+    if (!file) return -1;
+
+    size_t bytes_read = fread(buffer, 1, size, file);
+    fclose(file);
+    return (long)bytes_read;
+}
+
+/**
+ * @brief Checks the file content for specific patterns to determine the file type
+ *
+ * Only the first line of the content is examined.
+ *
+ * @param buffer The first bytes of the file
+ * @param length Number of bytes in the buffer
  * @return A dynamically allocated string containing the emoji, or NULL if no match
  */
-static char* check_file_content(const char *path) {
-    FILE *file = fopen(path, "r");
-    if (!file) return NULL;
-
-    char buffer[256];
-    if (fgets(buffer, sizeof(buffer), file) != NULL) {
-        // Check each entry in the file content map
-        for (size_t i = 0; i < emoji_file_content_map_size; i++) {
-            if (strstr(buffer, emoji_file_content_map[i].key) != NULL) {
-                fclose(file);
-                return safe_strdup(emoji_file_content_map[i].emoji);
-            }
-        }
+static char* check_file_content(const unsigned char *buffer, size_t length) {
+    char line[256];
+    size_t line_len = 0;
+    while (line_len < length && line_len < sizeof(line) - 1 && buffer[line_len] != '\n') {
+        line_len++;
     }
+    memcpy(line, buffer, line_len);
+    line[line_len] = '\0';
 
-    fclose(file);
+    // Check each entry in the file content map
+    for (size_t i = 0; i < emoji_file_content_map_size; i++) {
+        if (strstr(line, emoji_file_content_map[i].key) != NULL) {
+            return safe_strdup(emoji_file_content_map[i].emoji);
+        }
+    }
     return NULL;
 }
 
 /**
- * @brief Determines the appropriate emoji for a given file based on its characteristics
+ * @brief Returns the length of a UTF-8 multibyte sequence in a buffer
  *
- * @param path The path to the file
+ * A sequence cut off by the end of the buffer is accepted as long as the
+ * bytes that are present are valid, since only the head of the file is read.
+ *
+ * @param s Pointer to the lead byte of the sequence
+ * @param remaining Number of bytes left in the buffer, at least 1
+ * @return Length of the sequence in bytes, or 0 if it is not valid UTF-8
+ */
+static size_t utf8_sequence_length(const unsigned char *s, size_t remaining) {
+    size_t len;
+    if (s[0] >= 0xC2 && s[0] <= 0xDF) len = 2;
+    else if (s[0] >= 0xE0 && s[0] <= 0xEF) len = 3;
+    else if (s[0] >= 0xF0 && s[0] <= 0xF4) len = 4;
+    else return 0;  // Continuation byte, overlong lead or out of range
+
+    size_t available = len < remaining ? len : remaining;
+    for (size_t i = 1; i < available; i++) {
+        if ((s[i] & 0xC0) != 0x80) return 0;  // Missing continuation byte
+    }
+
+    // Reject overlong forms, surrogates and code points above U+10FFFF
+    if (available > 1) {
+        if (s[0] == 0xE0 && s[1] < 0xA0) return 0;
+        if (s[0] == 0xED && s[1] >= 0xA0) return 0;
+        if (s[0] == 0xF0 && s[1] < 0x90) return 0;
+        if (s[0] == 0xF4 && s[1] >= 0x90) return 0;
+    }
+    return available;
+}
+
+/**
+ * @brief Checks if the beginning of a file looks like text
+ *
+ * @param buffer The first bytes of the file
+ * @param length Number of bytes in the buffer
+ * @return 1 if the bytes are valid UTF-8 without control characters
+ *         other than whitespace, 0 otherwise
+ */
+static int is_text_content(const unsigned char *buffer, size_t length) {
+    size_t i = 0;
+    while (i < length) {
+        if (buffer[i] < 0x80) {
+            if (!isprint(buffer[i]) && !isspace(buffer[i])) {
+                return 0; // Control character found
+            }
+            i++;
+            continue;
+        }
+
+        // Accented letters, CJK and the like are text as long as they are valid UTF-8
+        size_t len = utf8_sequence_length(buffer + i, length - i);
+        if (len == 0) {
+            return 0; // Invalid UTF-8, most likely binary data
+        }
+        i += len;
+    }
+    return 1; // Everything is text (an empty file is text too)
+}
+
+/**
+ * @brief Determines the appropriate emoji for a symbolic link based on its target
+ *
+ * @param target_mode The mode of the link's target, 0 if it cannot be reached
  * @return A dynamically allocated string containing the emoji
  */
-char *get_emoji(const char *path) {
-   struct stat path_stat;
+char *get_symlink_emoji(mode_t target_mode) {
+    // A link whose target cannot be reached is broken
+    if (target_mode == 0) {
+        return safe_strdup("🔗❌");
+    }
 
-    // Check if we can get file information
-    if (lstat(path, &path_stat) != 0) {
-        return safe_strdup("❓");  // Unknown file type
+    if (S_ISDIR(target_mode)) {
+        return safe_strdup("🔗📁");
     }
 
-    // Check for symbolic links
-    if (S_ISLNK(path_stat.st_mode)) {
-        return safe_strdup(S_ISDIR(path_stat.st_mode) ? "🔗📁" : "🔗");
+    if (S_ISREG(target_mode) && (target_mode & S_IXUSR)) {
+        return safe_strdup("🔗💾");
+    }
+
+    return safe_strdup("🔗");
+}
+
+/**
+ * @brief Finds the last two extension components of a file name
+ *
+ * @param filename The file name, e.g. "backup.tar.gz"
+ * @return Pointer to the compound extension without the leading dot
+ *         (e.g. "tar.gz"), or NULL if the name has fewer than two extensions
+ */
+static const char *find_compound_extension(const char *filename) {
+    const char *last_dot = strrchr(filename, '.');
+    if (!last_dot || last_dot == filename) {
+        return NULL;
+    }
+
+    // Walk back to the dot before the last one
+    const char *dot = last_dot;
+    while (dot > filename && *(dot - 1) != '.') {
+        dot--;
+    }
+    if (dot == filename || dot - 1 == filename) {
+        return NULL;  // Only one extension, or a hidden file like ".config.gz"
+    }
+    return dot;
+}
+
+/**
+ * @brief Determines the appropriate emoji for a given file based on its characteristics
+ *
+ * @param path The path to the file
+ * @param mode The file type and permission bits, as reported by lstat
+ * @return A dynamically allocated string containing the emoji
+ */
+char *get_emoji(const char *path, mode_t mode, mode_t target_mode) {
+    // Check for symbolic links, looking at the target they point to
+    if (S_ISLNK(mode)) {
+        return get_symlink_emoji(target_mode);
     }
 
     // Check for directories
-    if (S_ISDIR(path_stat.st_mode)) {
+    if (S_ISDIR(mode)) {
         return safe_strdup("📁");
     }
 
@@ -119,27 +241,54 @@ char *get_emoji(const char *path) {
     const char *filename = strrchr(path, '/');
     filename = filename ? filename + 1 : path;
 
-    // Check for not-exact file name matches first (top highest priority)
-    for (size_t i = 0; i < emoji_not_exact_file_map_size; i++) {
-        if (strstr(filename, emoji_not_exact_file_map[i].key) != NULL) {
-            return safe_strdup(emoji_not_exact_file_map[i].emoji);
-        }
+    // Check user mappings from the configuration file before the built-in tables
+    const char *user_emoji = find_user_file_emoji(filename);
+    const char *user_extension = strrchr(filename, '.');
+    const char *user_compound = find_compound_extension(filename);
+    if (!user_emoji && user_compound) {
+        user_emoji = find_user_extension_emoji(user_compound);
+    }
+    if (!user_emoji && user_extension && user_extension != filename) {
+        user_emoji = find_user_extension_emoji(user_extension + 1);
+    }
+    if (user_emoji) {
+        return safe_strdup(user_emoji);
     }
 
-    // Check for exact file name matches first (highest priority)
+    // Check for exact file name matches first, ignoring case (highest priority)
     for (size_t i = 0; i < emoji_exact_file_map_size; i++) {
         if (strcasecmp(filename, emoji_exact_file_map[i].key) == 0) {
             return safe_strdup(emoji_exact_file_map[i].emoji);
         }
     }
 
+    // Check for not-exact file name matches next (e.g. "Makefile" in "Makefile.win")
+    for (size_t i = 0; i < emoji_not_exact_file_map_size; i++) {
+        if (strstr(filename, emoji_not_exact_file_map[i].key) != NULL) {
+            return safe_strdup(emoji_not_exact_file_map[i].emoji);
+        }
+    }
+
+    // Only regular files are read, devices and pipes could block or have side effects
+    unsigned char head[1024];
+    long head_len = S_ISREG(mode) ? read_file_head(path, head, sizeof(head)) : -1;
+
     // Check file content for specific patterns (second priority)
-    char *content_emoji = check_file_content(path);
+    char *content_emoji = head_len > 0 ? check_file_content(head, (size_t)head_len) : NULL;
     if (content_emoji) {
         return content_emoji;
     }
 
-    // Check file extensions (third priority)
+    // Check file extensions (third priority), compound ones like "tar.gz" first
+    const char *compound = find_compound_extension(filename);
+    if (compound) {
+        for (size_t i = 0; i < emoji_extension_map_size; i++) {
+            if (strcasecmp(compound, emoji_extension_map[i].key) == 0) {
+                return safe_strdup(emoji_extension_map[i].emoji);
+            }
+        }
+    }
+
     char *extension = strrchr(filename, '.');
     if (extension) {
         extension++;  // Skip the dot
@@ -150,64 +299,26 @@ char *get_emoji(const char *path) {
         }
     }
 
+    // Scripts with a shebang for an interpreter not covered above
+    if (head_len >= 2 && head[0] == '#' && head[1] == '!') {
+        return safe_strdup("💻");
+    }
+
     // Check for hidden files
     if (filename[0] == '.') {
         return safe_strdup("⚙️ ");
     }
 
     // Check for executable files
-    if (is_executable(path)) {
+    if (mode & S_IXUSR) {
         return safe_strdup("💾");
     }
 
     // Check for text files
-    if (is_text_file(path)) {
+    if (head_len >= 0 && is_text_content(head, (size_t)head_len)) {
         return safe_strdup("📝");
     }
 
     // Default emoji for unclassified files
     return safe_strdup("❓");
 }
-
-/**
- * @brief Checks if a file is executable
- *
- * @param path The path to the file
- * @return 1 if the file is executable, 0 otherwise
- */
-int is_executable(const char *path) {
-    struct stat st;
-    if (stat(path, &st) == 0) {
-        return (st.st_mode & S_IXUSR) != 0; // Check if the user execute bit is set
-    }
-    return 0;
-}
-
-/**
- * @brief Checks if a file is a text file by examining its contents
- *
- * @param path The path to the file
- * @return 1 if the file is likely a text file, 0 otherwise
- */
-int is_text_file(const char *path) {
-    FILE *file = fopen(path, "rb");
-    if (!file) {
-        return 0; // Cannot open file, assume it's not text
-    }
-
-    unsigned char buffer[1024];
-    size_t bytesRead = fread(buffer, 1, sizeof(buffer), file);
-    fclose(file);
-
-    if (bytesRead == 0) {
-        return 1;  // Empty file is considered text
-    }
-
-    // Check if all read bytes are printable or whitespace
-    for (size_t i = 0; i < bytesRead; i++) {
-        if (!isprint(buffer[i]) && !isspace(buffer[i])) {
-            return 0; // Non-printable, non-whitespace character found
-        }
-    }
-    return 1; // All characters are printable or whitespace
-}
@@ -13,6 +13,8 @@
 #ifndef EMOJI_UTILS_H
 #define EMOJI_UTILS_H
 
+#include <sys/types.h>
+
 #ifdef __cplusplus
 extern "C" {
 #endif
@@ -23,33 +25,36 @@ extern "C" {
  * This function analyzes the file at the given path and returns an emoji
  * representing its type (e.g., directory, text file, image, etc.).
  *
+ * File names and extensions are matched ignoring case, so "Photo.PNG"
+ * gets the same emoji as "photo.png".
+ *
+ * The file type and permissions are taken from the caller's lstat
+ * result, and those of a symbolic link's target from the caller's stat
+ * result, so the file is not stat'ed again. Only regular files are
+ * opened to examine their contents.
+ *
  * @param path The file path to analyze.
+ * @param mode The file type and permission bits, as reported by lstat.
+ * @param target_mode The mode of a symbolic link's target as reported by
+ *        stat, 0 if the target cannot be reached; ignored for other files.
  * @return A dynamically allocated string containing the emoji.
  *         The caller is responsible for freeing this memory.
  */
-char *get_emoji(const char *path);
-
-/**
- * @brief Checks if a file is executable.
- *
- * This function determines whether the file at the given path has
- * execute permissions.
- *
- * @param path The file path to check.
- * @return 1 if the file is executable, 0 otherwise.
- */
-int is_executable(const char *path);
+char *get_emoji(const char *path, mode_t mode, mode_t target_mode);
 
 /**
- * @brief Checks if a file is a text file.
+ * @brief Determines the appropriate emoji for a symbolic link.
  *
- * This function analyzes the content of the file to determine if it's
- * likely to be a text file.
+ * This function returns an emoji describing the target of the link: a
+ * directory, an executable, any other file, or a broken link whose
+ * target does not exist.
  *
- * @param path The file path to check.
- * @return 1 if the file is likely a text file, 0 otherwise.
+ * @param target_mode The mode of the target as reported by stat, 0 if
+ *        the target cannot be reached.
+ * @return A dynamically allocated string containing the emoji.
+ *         The caller is responsible for freeing this memory.
  */
-int is_text_file(const char *path);
+char *get_symlink_emoji(mode_t target_mode);
 
 /**
  * @brief Determines the appropriate emoji for a device file in the /dev directory.
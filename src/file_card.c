@@ -10,6 +10,8 @@
  * @date 2024
  */
 
+#include <stdio.h>
+#include <errno.h>
 #include <stdlib.h>
 #include <string.h>
 #include <sys/stat.h>
@@ -19,51 +21,310 @@
 #include "file_card.h"
 
 /**
- * @brief Creates a new file card based on the given path.
+ * @brief Normalizes a path without looking at the file system.
  *
- * This function initializes a FileCardInfo structure with information about the file,
- * including its name, emoji representation, and flags for directory and hidden status.
+ * Repeated slashes and "." components are removed, and ".." removes the
+ * component before it, so "./a//b/../c" becomes "a/c". A ".." that has
+ * nothing to remove is kept, or dropped right after the root.
+ *
+ * @param path The path to normalize.
+ * @return Newly allocated normalized path, or NULL if memory runs out.
+ */
+static char *normalize_path(const char *path)
+{
+    size_t length = strlen(path);
+    char *result = malloc(length + 2);  // Room for "." and the terminator
+    if (!result)
+    {
+        return NULL;
+    }
+
+    int absolute = (path[0] == '/');
+    size_t out = absolute ? 1 : 0;  // Length of the normalized part so far
+    size_t root = out;              // Components before this cannot be removed
+    result[0] = '/';
+
+    const char *p = path;
+    while (*p)
+    {
+        // Take the next component between slashes
+        while (*p == '/')
+        {
+            p++;
+        }
+        const char *start = p;
+        while (*p && *p != '/')
+        {
+            p++;
+        }
+        size_t part = p - start;
+
+        if (part == 0 || (part == 1 && start[0] == '.'))
+        {
+            continue;  // Empty or "." component
+        }
+        int parent = (part == 2 && start[0] == '.' && start[1] == '.');
+        if (parent && out > root)
+        {
+            // Remove the previous component and the slash before it
+            size_t cut = out;
+            while (cut > root && result[cut - 1] != '/')
+            {
+                cut--;
+            }
+            out = cut > root ? cut - 1 : root;
+            continue;
+        }
+        if (parent && absolute)
+        {
+            continue;  // "/.." is the root itself
+        }
+
+        if (out > (absolute ? 1 : 0))
+        {
+            result[out++] = '/';
+        }
+        memcpy(result + out, start, part);
+        out += part;
+        if (parent)
+        {
+            root = out;  // Leading ".." components are kept
+        }
+    }
+
+    if (out == 0)
+    {
+        result[out++] = '.';  // The current directory
+    }
+    result[out] = '\0';
+    return result;
+}
+
+/**
+ * @brief Fills a file card for a file that is examined through one path and listed under another name.
  *
  * @param entry Pointer to a FileCardInfo structure to be filled.
- * @param path Path of the file to analyze.
+ * @param path Path used to examine the file.
+ * @param name Name stored in the entry.
  * @return 0 on success, -1 on error.
  */
-int create_file_entry(FileCardInfo *entry, const char *path)
+static int fill_file_entry(FileCardInfo *entry, const char *path, const char *name)
 {
-    // Duplicate the file name and store it in the entry
-    entry->name = strdup(path);
-    // Get the appropriate emoji for the file
-    entry->emoji = get_emoji(path);
-
     struct stat path_stat;
-    // Get file status information
+    // Get file status information before allocating anything
     if (lstat(path, &path_stat) != 0)
     {
         return -1;  // Return -1 if unable to get file status
     }
 
+    // Duplicate the file name and store it in the entry
+    entry->name = strdup(name);
+    entry->display_name = make_display_name(name);
+    entry->path = normalize_path(path);
+    if (!entry->name || !entry->display_name || !entry->path)
+    {
+        free(entry->name);
+        free(entry->display_name);
+        free(entry->path);
+        return -1;  // Return -1 if the name could not be allocated
+    }
+    // Links are colored like their target with ln=target in LS_COLORS, and get its emoji
+    struct stat target_stat;
+    entry->target_mode = (S_ISLNK(path_stat.st_mode) && stat(path, &target_stat) == 0) ? target_stat.st_mode : 0;
+    // Get the appropriate emoji for the file, without examining it again
+    entry->emoji = get_emoji(path, path_stat.st_mode, entry->target_mode);
+
     // Set directory flag based on file type
     entry->is_directory = S_ISDIR(path_stat.st_mode);
     // Set hidden flag if the file name starts with a dot
-    entry->is_hidden = (path[0] == '.');
+    entry->is_hidden = (name[0] == '.');
+    // Keep type, size and modification time for sorting and summaries
+    entry->mode = path_stat.st_mode;
+    entry->size = path_stat.st_size;
+    entry->mtime = path_stat.st_mtime;
     // Initialize Git status as empty
     entry->git_status[0] = '\0';
     entry->git_status[1] = '\0';
+    entry->git_staged = 0;
+    entry->contains_modified = 0;
 
     return 0;  // Return 0 on success
 }
 
+/**
+ * @brief Creates a new file card based on the given path.
+ *
+ * This function initializes a FileCardInfo structure with information about the file,
+ * including its name, emoji representation, and flags for directory and hidden status.
+ *
+ * @param entry Pointer to a FileCardInfo structure to be filled.
+ * @param path Path of the file to analyze.
+ * @return 0 on success, -1 on error.
+ */
+int create_file_entry(FileCardInfo *entry, const char *path)
+{
+    return fill_file_entry(entry, path, path);
+}
+
+/**
+ * @brief Creates a new file card for an entry of a directory.
+ *
+ * @param entry Pointer to a FileCardInfo structure to be filled.
+ * @param dir_path Path of the directory containing the file.
+ * @param name Name of the file inside the directory.
+ * @return 0 on success, -1 on error.
+ */
+int create_file_entry_in(FileCardInfo *entry, const char *dir_path, const char *name)
+{
+    char path[4096];
+    if (snprintf(path, sizeof(path), "%s/%s", dir_path, name) >= (int)sizeof(path))
+    {
+        errno = ENAMETOOLONG;
+        return -1;  // Path too long to examine
+    }
+    return fill_file_entry(entry, path, name);
+}
+
+/**
+ * @brief Creates a placeholder file entry for a file that cannot be examined.
+ *
+ * The entry keeps its name so it can still be listed, and gets the unknown
+ * file emoji with empty metadata.
+ *
+ * @param entry Pointer to a FileCardInfo structure to be filled.
+ * @param name Name of the file.
+ * @return 0 on success, -1 on allocation failure.
+ */
+int create_unknown_file_entry(FileCardInfo *entry, const char *name)
+{
+    entry->name = strdup(name);
+    entry->display_name = make_display_name(name);
+    entry->emoji = strdup("❓");
+    entry->path = NULL;  // Never equal to another entry
+    if (!entry->name || !entry->display_name || !entry->emoji)
+    {
+        free(entry->name);
+        free(entry->display_name);
+        free(entry->emoji);
+        return -1;
+    }
+
+    entry->is_directory = 0;
+    entry->is_hidden = (name[0] == '.');
+    entry->mode = 0;  // Unknown type, counted as "other" in summaries
+    entry->target_mode = 0;
+    entry->size = 0;
+    entry->mtime = 0;
+    entry->git_status[0] = '\0';
+    entry->git_status[1] = '\0';
+    entry->git_staged = 0;
+    entry->contains_modified = 0;
+    return 0;
+}
+
+/**
+ * @brief Returns the length of a valid UTF-8 sequence.
+ *
+ * @param s Pointer to the first byte of the sequence.
+ * @return Length of the sequence in bytes, or 0 if it is not valid UTF-8.
+ */
+static size_t valid_utf8_length(const unsigned char *s)
+{
+    size_t len;
+    if (s[0] < 0x80) return 1;
+    else if (s[0] >= 0xC2 && s[0] <= 0xDF) len = 2;
+    else if (s[0] >= 0xE0 && s[0] <= 0xEF) len = 3;
+    else if (s[0] >= 0xF0 && s[0] <= 0xF4) len = 4;
+    else return 0;  // Continuation byte, overlong lead or out of range
+
+    for (size_t i = 1; i < len; i++)
+    {
+        if ((s[i] & 0xC0) != 0x80) return 0;  // Missing continuation byte
+    }
+
+    // Reject overlong forms, surrogates and code points above U+10FFFF
+    if (len == 3 && s[0] == 0xE0 && s[1] < 0xA0) return 0;
+    if (len == 3 && s[0] == 0xED && s[1] >= 0xA0) return 0;
+    if (len == 4 && s[0] == 0xF0 && s[1] < 0x90) return 0;
+    if (len == 4 && s[0] == 0xF4 && s[1] >= 0x90) return 0;
+    return len;
+}
+
+/**
+ * @brief Checks whether a string is valid UTF-8.
+ *
+ * @param str The string to check.
+ * @return 1 if every byte belongs to a valid UTF-8 sequence, 0 otherwise.
+ */
+int is_valid_utf8(const char *str)
+{
+    const unsigned char *in = (const unsigned char *)str;
+    while (*in)
+    {
+        size_t len = valid_utf8_length(in);
+        if (len == 0)
+        {
+            return 0;
+        }
+        in += len;
+    }
+    return 1;
+}
+
+/**
+ * @brief Creates a printable version of a file name.
+ *
+ * @param name The raw file name.
+ * @return A newly allocated printable name, or NULL on allocation failure.
+ */
+char *make_display_name(const char *name)
+{
+    // Every byte expands to at most four characters ("\xNN")
+    char *display = malloc(strlen(name) * 4 + 1);
+    if (!display) return NULL;
+
+    const unsigned char *in = (const unsigned char *)name;
+    char *out = display;
+    while (*in)
+    {
+        size_t len = valid_utf8_length(in);
+        if (len == 0 || (len == 1 && (*in < 0x20 || *in == 0x7F)))
+        {
+            // Show the offending byte instead of printing it raw
+            out += sprintf(out, "\\x%02X", *in);
+            in++;
+        }
+        else if (*in == '\\')
+        {
+            // Double literal backslashes so they cannot be mistaken for an escape
+            *out++ = '\\';
+            *out++ = '\\';
+            in++;
+        }
+        else
+        {
+            memcpy(out, in, len);
+            out += len;
+            in += len;
+        }
+    }
+    *out = '\0';
+    return display;
+}
+
 /**
  * @brief Frees memory allocated for a file entry.
  *
- * This function releases the memory allocated for the name and emoji fields
- * of a FileCardInfo structure.
+ * This function releases the memory allocated for the name, display name
+ * and emoji fields of a FileCardInfo structure.
  *
  * @param entry Pointer to the FileCardInfo structure to free.
  */
 void free_file_entry(FileCardInfo *entry)
 {
     free(entry->name);  // Free the memory allocated for the name string
+    free(entry->path);  // Free the memory allocated for the normalized path
+    free(entry->display_name); // Free the memory allocated for the printable name
     free(entry->emoji); // Free the memory allocated for the emoji string
 }
 
@@ -117,6 +378,56 @@ static int strcasecmp_wrapper(const char *a, const char *b) {
     return tolower((unsigned char)*a) - tolower((unsigned char)*b);
 }
 
+/**
+ * @brief Compare two names case-insensitively, falling back to an exact comparison.
+ *
+ * Names that differ only in case, like "README" and "readme" on a
+ * case-sensitive file system, are still different files, so they never
+ * compare as equal and always keep the same order.
+ *
+ * @param a The first name to compare.
+ * @param b The second name to compare.
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b.
+ */
+static int compare_names(const char *a, const char *b) {
+    int cmp = strcasecmp_wrapper(a, b);
+    return cmp != 0 ? cmp : strcmp(a, b);
+}
+
+/**
+ * @brief Checks whether two file entries refer to the same file.
+ *
+ * @param a The first entry.
+ * @param b The second entry.
+ * @return 1 if the entries have the same path, 0 otherwise.
+ */
+int file_entries_equal(const FileCardInfo *a, const FileCardInfo *b)
+{
+    if (!a->path || !b->path)
+    {
+        return 0;  // Placeholders for files that could not be examined
+    }
+    return strcmp(a->path, b->path) == 0;
+}
+
+/**
+ * @brief Orders directories before files when grouping is enabled.
+ *
+ * @param entry_a The first entry.
+ * @param entry_b The second entry.
+ * @param options Sort settings.
+ * @return Negative if only entry_a is a directory, positive if only entry_b
+ *         is, zero if both are of the same kind or grouping is disabled.
+ */
+static int compare_entry_types(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options)
+{
+    if (!options->group_directories_first) {
+        return 0;
+    }
+    return entry_b->is_directory - entry_a->is_directory;
+}
+
 /**
  * @brief Compare two file entries for sorting.
  *
@@ -126,29 +437,29 @@ static int strcasecmp_wrapper(const char *a, const char *b) {
  * 3. Files sorted by extension
  * 4. Alphabetical sorting within the same type and extension
  *
- * @param a Pointer to the first FileCardInfo structure.
- * @param b Pointer to the second FileCardInfo structure.
+ * @param entry_a The first entry.
+ * @param entry_b The second entry.
+ * @param options Sort settings such as directory grouping.
  * @return An integer less than, equal to, or greater than zero if a is found,
  *         respectively, to be less than, to match, or be greater than b.
  */
-int compare_file_entries(const void *a, const void *b)
+int compare_file_entries(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options)
 {
-    const FileCardInfo *entry_a = (const FileCardInfo *)a;
-    const FileCardInfo *entry_b = (const FileCardInfo *)b;
 
     // Sort directories before files
-    if (entry_a->is_directory != entry_b->is_directory) {
-        return entry_b->is_directory - entry_a->is_directory;
+    int type_cmp = compare_entry_types(entry_a, entry_b, options);
+    if (type_cmp != 0) {
+        return type_cmp;
     }
 
     // For directories, sort dotfiles first
-    if (entry_a->is_directory && entry_b->is_directory) {
+    if (options->group_directories_first && entry_a->is_directory && entry_b->is_directory) {
         // Check if one is a dotfile and the other isn't
         if ((entry_a->name[0] == '.') != (entry_b->name[0] == '.')) {
             return (entry_b->name[0] == '.') - (entry_a->name[0] == '.');
         }
         // Both are either dotfiles or non-dotfiles, sort alphabetically
-        return strcasecmp_wrapper(entry_a->name, entry_b->name);
+        return compare_names(entry_a->name, entry_b->name);
     }
 
     // For files, sort dotfiles first
@@ -167,5 +478,233 @@ int compare_file_entries(const void *a, const void *b)
     }
 
     // If extensions are the same, sort alphabetically by name
-    return strcasecmp_wrapper(entry_a->name, entry_b->name);
+    return compare_names(entry_a->name, entry_b->name);
+}
+
+/**
+ * @brief Compare two file entries by modification time.
+ *
+ * Directories come before files, newer entries before older ones,
+ * and ties are broken by the regular name ordering.
+ *
+ * @param entry_a The first entry.
+ * @param entry_b The second entry.
+ * @param options Sort settings such as directory grouping.
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b.
+ */
+int compare_file_entries_by_time(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options)
+{
+
+    // Sort directories before files
+    int type_cmp = compare_entry_types(entry_a, entry_b, options);
+    if (type_cmp != 0) {
+        return type_cmp;
+    }
+
+    // Newest entries first
+    if (entry_a->mtime != entry_b->mtime) {
+        return (entry_a->mtime < entry_b->mtime) ? 1 : -1;
+    }
+
+    // Equal times keep the usual order for stable output
+    return compare_file_entries(entry_a, entry_b, options);
+}
+
+/**
+ * @brief Compare two file entries by name.
+ *
+ * Directories come before files, and entries are ordered alphabetically
+ * without looking at the extension.
+ *
+ * @param entry_a The first entry.
+ * @param entry_b The second entry.
+ * @param options Sort settings such as directory grouping.
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b.
+ */
+int compare_file_entries_by_name(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options)
+{
+
+    // Sort directories before files
+    int type_cmp = compare_entry_types(entry_a, entry_b, options);
+    if (type_cmp != 0) {
+        return type_cmp;
+    }
+
+    // Names differing only in case keep a fixed order
+    return compare_names(entry_a->name, entry_b->name);
+}
+
+/**
+ * @brief Compare two file entries by size.
+ *
+ * Directories come before files, larger entries before smaller ones,
+ * and ties are broken by the regular name ordering.
+ *
+ * @param entry_a The first entry.
+ * @param entry_b The second entry.
+ * @param options Sort settings such as directory grouping.
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b.
+ */
+int compare_file_entries_by_size(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options)
+{
+
+    // Sort directories before files
+    int type_cmp = compare_entry_types(entry_a, entry_b, options);
+    if (type_cmp != 0) {
+        return type_cmp;
+    }
+
+    // Largest entries first
+    if (entry_a->size != entry_b->size) {
+        return (entry_a->size < entry_b->size) ? 1 : -1;
+    }
+
+    // Equal sizes keep the usual order for stable output
+    return compare_file_entries(entry_a, entry_b, options);
+}
+
+/**
+ * @brief Compare two strings, treating runs of digits as numbers.
+ *
+ * Numbers are compared digit by digit after skipping leading zeros, so
+ * they can be arbitrarily long without overflowing.
+ *
+ * @param a The first string to compare.
+ * @param b The second string to compare.
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b.
+ */
+static int natural_compare(const char *a, const char *b)
+{
+    int zeros_cmp = 0;  // First difference in leading zeros, used only as a tie-breaker
+
+    while (*a && *b) {
+        if (isdigit((unsigned char)*a) && isdigit((unsigned char)*b)) {
+            // Skip leading zeros, remembering which number had fewer
+            const char *start_a = a, *start_b = b;
+            while (*a == '0') a++;
+            while (*b == '0') b++;
+            if (zeros_cmp == 0) {
+                zeros_cmp = (int)(a - start_a) - (int)(b - start_b);
+            }
+
+            // A longer run of significant digits is a larger number
+            size_t len_a = 0, len_b = 0;
+            while (isdigit((unsigned char)a[len_a])) len_a++;
+            while (isdigit((unsigned char)b[len_b])) len_b++;
+            if (len_a != len_b) {
+                return len_a < len_b ? -1 : 1;
+            }
+
+            // Same length, so the first differing digit decides
+            int digits_cmp = strncmp(a, b, len_a);
+            if (digits_cmp != 0) {
+                return digits_cmp;
+            }
+            a += len_a;
+            b += len_b;
+            continue;
+        }
+
+        int ca = tolower((unsigned char)*a);
+        int cb = tolower((unsigned char)*b);
+        if (ca != cb) return ca - cb;
+        a++;
+        b++;
+    }
+
+    int rest_cmp = tolower((unsigned char)*a) - tolower((unsigned char)*b);
+    return rest_cmp != 0 ? rest_cmp : zeros_cmp;
+}
+
+/**
+ * @brief Compare two file entries by name with natural number ordering.
+ *
+ * Directories come before files, and names that compare equal fall back
+ * to a byte-wise comparison for a stable order.
+ *
+ * @param entry_a The first entry.
+ * @param entry_b The second entry.
+ * @param options Sort settings such as directory grouping.
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b.
+ */
+int compare_file_entries_by_version(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options)
+{
+
+    // Sort directories before files
+    int type_cmp = compare_entry_types(entry_a, entry_b, options);
+    if (type_cmp != 0) {
+        return type_cmp;
+    }
+
+    int cmp = natural_compare(entry_a->name, entry_b->name);
+    return cmp != 0 ? cmp : strcmp(entry_a->name, entry_b->name);
+}
+
+/**
+ * @brief Merges two sorted runs of entries.
+ *
+ * @param entries The entries, holding the runs [0, middle) and [middle, num_entries).
+ * @param middle Start of the second run.
+ * @param num_entries Number of entries in both runs.
+ * @param buffer Scratch space for num_entries entries.
+ * @param compare The comparator.
+ * @param options Sort settings passed to the comparator.
+ */
+static void merge_entries(FileCardInfo *entries, int middle, int num_entries, FileCardInfo *buffer,
+                          FileCardComparator compare, const SortOptions *options)
+{
+    int left = 0;
+    int right = middle;
+    int out = 0;
+    while (left < middle && right < num_entries) {
+        // Taking from the left on ties keeps the sort stable
+        if (compare(&entries[right], &entries[left], options) < 0) {
+            buffer[out++] = entries[right++];
+        } else {
+            buffer[out++] = entries[left++];
+        }
+    }
+    while (left < middle) buffer[out++] = entries[left++];
+    while (right < num_entries) buffer[out++] = entries[right++];
+    memcpy(entries, buffer, num_entries * sizeof(FileCardInfo));
+}
+
+/**
+ * @brief Sorts entries with a comparator and sort settings.
+ *
+ * A merge sort is used so that the settings can be handed to the
+ * comparator without global state.
+ *
+ * @param entries Array of FileCardInfo structures to sort in place.
+ * @param num_entries Number of entries in the array.
+ * @param compare The comparator, e.g. compare_file_entries.
+ * @param options Sort settings passed to the comparator.
+ * @return 0 on success, -1 if the scratch space cannot be allocated.
+ */
+int sort_file_entries(FileCardInfo *entries, int num_entries, FileCardComparator compare, const SortOptions *options)
+{
+    if (num_entries < 2) {
+        return 0;
+    }
+
+    FileCardInfo *buffer = malloc(num_entries * sizeof(FileCardInfo));
+    if (!buffer) {
+        return -1;
+    }
+
+    // Bottom-up: merge runs of width 1, 2, 4, ... until one run is left
+    for (int width = 1; width < num_entries; width *= 2) {
+        for (int start = 0; start + width < num_entries; start += 2 * width) {
+            int end = start + 2 * width < num_entries ? start + 2 * width : num_entries;
+            merge_entries(entries + start, width, end - start, buffer, compare, options);
+        }
+    }
+
+    free(buffer);
+    return 0;
 }
@@ -14,21 +14,32 @@
 #ifndef FILE_ENTRY_H
 #define FILE_ENTRY_H
 
+#include <time.h>
+#include <sys/types.h>
+
 /**
  * @struct FileCardInfo
  * @brief Represents information about a file or directory entry
  *
  * This structure contains various attributes of a file or directory,
  * including its name, emoji representation, flags for directory and
- * hidden status, Git status, and a flag for modified contents.
+ * hidden status, size and modification time, Git status, and a flag
+ * for modified contents.
  */
 typedef struct
 {
-    char *name;              /**< Name of the file or directory */
+    char *name;              /**< Name of the file or directory, as raw bytes from the file system */
+    char *path;              /**< Path the entry was examined through, normalized; NULL if it could not be examined */
+    char *display_name;      /**< Name safe to print, with invalid UTF-8 and control bytes shown as \xNN */
     char *emoji;             /**< Emoji representation of the file type */
     int is_directory;        /**< Flag indicating if the entry is a directory (1) or file (0) */
     int is_hidden;           /**< Flag indicating if the entry is hidden (1) or visible (0) */
+    mode_t mode;             /**< File type and permission bits, as reported by lstat */
+    mode_t target_mode;      /**< Type and permission bits of a symbolic link's target, 0 if none */
+    off_t size;              /**< Size of the entry in bytes, as reported by lstat */
+    time_t mtime;            /**< Last modification time of the entry */
     char git_status[2];      /**< Git status of the file (2 characters for status code) */
+    int git_staged;          /**< Flag indicating if the Git change is staged (1) or only in the work tree (0) */
     int contains_modified;   /**< Flag indicating if a directory contains modified files (1) or not (0) */
 } FileCardInfo;
 
@@ -44,16 +55,92 @@ typedef struct
  */
 int create_file_entry(FileCardInfo *entry, const char *path);
 
+/**
+ * @brief Creates a new file entry for a file inside a directory
+ *
+ * The file is examined through its path relative to dir_path, but only
+ * its name is stored, so the directory need not be the working directory.
+ *
+ * @param entry Pointer to a FileCardInfo structure to be filled
+ * @param dir_path Path of the directory containing the file
+ * @param name Name of the file inside the directory
+ * @return 0 on success, -1 on error
+ */
+int create_file_entry_in(FileCardInfo *entry, const char *dir_path, const char *name);
+
+/**
+ * @brief Creates a printable version of a file name
+ *
+ * File names may contain bytes that are not valid UTF-8 or that are
+ * control characters. Such bytes are replaced with a visible \xNN escape,
+ * so the name can be told apart from a clean one and is measured correctly
+ * by the layout code. Literal backslashes are doubled, so every printable
+ * name belongs to exactly one raw name.
+ *
+ * @param name The raw file name
+ * @return A newly allocated printable name, or NULL on allocation failure
+ */
+char *make_display_name(const char *name);
+
+/**
+ * @brief Checks whether a string is valid UTF-8
+ *
+ * @param str The string to check
+ * @return 1 if every byte belongs to a valid UTF-8 sequence, 0 otherwise
+ */
+int is_valid_utf8(const char *str);
+
+/**
+ * @brief Creates a placeholder entry for a file that cannot be examined
+ *
+ * Used when a directory lists a file that lstat then fails on, e.g.
+ * because it vanished or permission was denied, so the file is still
+ * shown with the unknown file emoji instead of being dropped.
+ *
+ * @param entry Pointer to a FileCardInfo structure to be filled
+ * @param name Name of the file
+ * @return 0 on success, -1 on allocation failure
+ */
+int create_unknown_file_entry(FileCardInfo *entry, const char *name);
+
 /**
  * @brief Frees memory allocated for a file entry
  *
- * This function releases the memory allocated for the name and emoji
- * fields of a FileCardInfo structure.
+ * This function releases the memory allocated for the name, display name
+ * and emoji fields of a FileCardInfo structure.
  *
  * @param entry Pointer to the FileCardInfo structure to free
  */
 void free_file_entry(FileCardInfo *entry);
 
+/**
+ * @struct SortOptions
+ * @brief Settings shared by the comparators.
+ */
+typedef struct {
+    int group_directories_first; /**< List directories before files (1) or among them (0) */
+} SortOptions;
+
+/**
+ * @brief Comparator used by sort_file_entries
+ */
+typedef int (*FileCardComparator)(const FileCardInfo *entry_a, const FileCardInfo *entry_b,
+                                  const SortOptions *options);
+
+/**
+ * @brief Sorts entries with a comparator and sort settings
+ *
+ * The sort is stable, and the settings are passed to the comparator
+ * rather than kept in global state.
+ *
+ * @param entries Array of FileCardInfo structures to sort in place
+ * @param num_entries Number of entries in the array
+ * @param compare The comparator, e.g. compare_file_entries
+ * @param options Sort settings passed to the comparator
+ * @return 0 on success, -1 if memory for sorting cannot be allocated
+ */
+int sort_file_entries(FileCardInfo *entries, int num_entries, FileCardComparator compare, const SortOptions *options);
+
 /**
  * @brief Compares two file entries for sorting
  *
@@ -63,11 +150,95 @@ void free_file_entry(FileCardInfo *entry);
  * 3. Files sorted by extension
  * 4. Alphabetical sorting within the same type and extension
  *
- * @param a Pointer to the first FileCardInfo structure
- * @param b Pointer to the second FileCardInfo structure
+ * Without options->group_directories_first, step 1 is skipped and
+ * directories are sorted together with files by the same rules.
+ *
+ * Names are compared ignoring case, and names that differ only in case
+ * are ordered by their exact bytes, so only the same file compares equal.
+ *
+ * @param entry_a The first entry
+ * @param entry_b The second entry
+ * @param options Sort settings such as directory grouping
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b
+ */
+int compare_file_entries(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options);
+
+/**
+ * @brief Checks whether two file entries refer to the same file
+ *
+ * Entries are identified by their normalized path, compared byte for
+ * byte, so a/README and b/README are different files, and so are "README"
+ * and "readme" even though compare_file_entries sorts them next to each
+ * other. Paths are normalized without touching the file system: "./x",
+ * "x" and "a/../x" are the same path, while hard links, symbolic links
+ * and a relative and an absolute path to one file are not.
+ * Entries that could not be examined never equal another entry.
+ *
+ * @param a The first entry
+ * @param b The second entry
+ * @return 1 if the entries have the same path, 0 otherwise
+ */
+int file_entries_equal(const FileCardInfo *a, const FileCardInfo *b);
+
+/**
+ * @brief Compares two file entries by modification time for sorting
+ *
+ * Directories are still listed before files. Within each group newer
+ * entries come first, and entries with equal modification times fall
+ * back to the order of compare_file_entries.
+ *
+ * @param entry_a The first entry
+ * @param entry_b The second entry
+ * @param options Sort settings such as directory grouping
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b
+ */
+int compare_file_entries_by_time(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options);
+
+/**
+ * @brief Compares two file entries by name for sorting
+ *
+ * Directories are listed before files, and within each group entries
+ * are sorted alphabetically, ignoring case and extensions.
+ *
+ * @param entry_a The first entry
+ * @param entry_b The second entry
+ * @param options Sort settings such as directory grouping
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b
+ */
+int compare_file_entries_by_name(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options);
+
+/**
+ * @brief Compares two file entries by size for sorting
+ *
+ * Directories are still listed before files. Within each group larger
+ * entries come first, and entries of equal size fall back to the order
+ * of compare_file_entries.
+ *
+ * @param entry_a The first entry
+ * @param entry_b The second entry
+ * @param options Sort settings such as directory grouping
+ * @return An integer less than, equal to, or greater than zero if a is found,
+ *         respectively, to be less than, to match, or be greater than b
+ */
+int compare_file_entries_by_size(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options);
+
+/**
+ * @brief Compares two file entries by name with natural number ordering
+ *
+ * Directories are listed before files. Runs of digits in the names are
+ * compared by their numeric value, so "file2" comes before "file10";
+ * other characters are compared ignoring case. Numbers of any length are
+ * supported, and equal numbers with fewer leading zeros come first.
+ *
+ * @param entry_a The first entry
+ * @param entry_b The second entry
+ * @param options Sort settings such as directory grouping
  * @return An integer less than, equal to, or greater than zero if a is found,
  *         respectively, to be less than, to match, or be greater than b
  */
-int compare_file_entries(const void *a, const void *b);
+int compare_file_entries_by_version(const FileCardInfo *entry_a, const FileCardInfo *entry_b, const SortOptions *options);
 
 #endif // FILE_ENTRY_H
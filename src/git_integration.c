@@ -71,12 +71,14 @@ void integrate_git_status(FileCardInfo** entries, int* num_entries, const char*
     // Set git status for entries
     for (int i = 0; i < *num_entries; i++) {
         (*entries)[i].git_status[0] = '\0';
+        (*entries)[i].git_staged = 0;
         (*entries)[i].contains_modified = 0;
 
         // Set file status
         for (int j = 0; j < git_status.count; j++) {
             if (strcmp((*entries)[i].name, git_status.files[j].filename) == 0) {
                 (*entries)[i].git_status[0] = git_status.files[j].status;
+                (*entries)[i].git_staged = git_status.files[j].staged;
                 break;
             }
         }
@@ -1,20 +1,31 @@
 #include <stdio.h>
+#include <errno.h>
+#include <fcntl.h>
 #include <stdlib.h>
 #include <string.h>
 #include <unistd.h>
+#include <sys/stat.h>
+#include <sys/wait.h>
 #include "git_utils.h"
 
+#define MAX_PATH 4096
+
 bool is_git_repository(const char* path) {
-    char command[1024];
-    snprintf(command, sizeof(command), "git -C \"%s\" rev-parse --is-inside-work-tree 2>/dev/null", path);
-    
-    FILE* fp = popen(command, "r");
-    if (!fp) return false;
-    
-    char buffer[128];
-    bool result = fgets(buffer, sizeof(buffer), fp) && strcmp(buffer, "true\n") == 0;
-    pclose(fp);
-    return result;
+    char dir[MAX_PATH];
+    if (!realpath(path, dir)) return false;
+
+    // Walk up towards the root looking for a .git directory or file
+    while (true) {
+        char git_path[MAX_PATH + 5];
+        struct stat st;
+        snprintf(git_path, sizeof(git_path), "%s/.git", dir);
+        if (stat(git_path, &st) == 0) return true;
+        if (strcmp(dir, "/") == 0) return false;
+
+        char* slash = strrchr(dir, '/');
+        if (slash == dir) slash[1] = '\0';  // Parent is the root directory
+        else *slash = '\0';
+    }
 }
 
 char parse_status(const char* status_str) {
@@ -22,42 +33,139 @@ char parse_status(const char* status_str) {
     return status_str[0] != ' ' ? status_str[0] : status_str[1];
 }
 
+/**
+ * @brief Runs a git command in a directory and collects its output.
+ *
+ * git is started directly with an argument vector, without a shell, so
+ * the path is never interpreted, whatever characters it contains.
+ *
+ * @param path The directory to run git in.
+ * @param args The git arguments, terminated by NULL.
+ * @param length Set to the number of bytes read.
+ * @return Dynamically allocated output, NUL-terminated, or NULL on failure.
+ */
+static char* run_git(const char* path, const char* const* args, size_t* length) {
+    const char* argv[16] = {"git", "-C", path};
+    int argc = 3;
+    for (int i = 0; args[i] && argc < 15; i++) {
+        argv[argc++] = args[i];
+    }
+    argv[argc] = NULL;
+
+    int fds[2];
+    if (pipe(fds) != 0) return NULL;
+
+    pid_t pid = fork();
+    if (pid < 0) {
+        close(fds[0]);
+        close(fds[1]);
+        return NULL;
+    }
+    if (pid == 0) {
+        // Child: write to the pipe and keep git's errors out of the listing
+        int devnull = open("/dev/null", O_WRONLY);
+        if (devnull >= 0) dup2(devnull, STDERR_FILENO);
+        dup2(fds[1], STDOUT_FILENO);
+        close(fds[0]);
+        close(fds[1]);
+        execvp("git", (char* const*)argv);
+        _exit(127);
+    }
+    close(fds[1]);
+
+    size_t size = 4096;
+    size_t used = 0;
+    char* output = malloc(size);
+    while (output) {
+        ssize_t n = read(fds[0], output + used, size - used - 1);
+        if (n < 0 && errno == EINTR) continue;
+        if (n <= 0) break;  // End of output
+        used += n;
+        if (used == size - 1) {
+            char* grown = realloc(output, size * 2);
+            if (!grown) {
+                free(output);
+                output = NULL;
+                break;
+            }
+            output = grown;
+            size *= 2;
+        }
+    }
+    close(fds[0]);
+
+    int status;
+    while (waitpid(pid, &status, 0) < 0 && errno == EINTR) {}
+    if (!output || !WIFEXITED(status) || WEXITSTATUS(status) != 0) {
+        free(output);  // git failed or is missing, e.g. outside of a work tree
+        return NULL;
+    }
+    output[used] = '\0';
+    *length = used;
+    return output;
+}
+
 GitFileList get_git_status(const char* path) {
     GitFileList list = {0};
-    char command[1024];
-    snprintf(command, sizeof(command), "git -C \"%s\" status -s", path);
-    
-    FILE* fp = popen(command, "r");
-    if (!fp) return list;
-
-    char line[MAX_FILENAME + 3];
-    while (fgets(line, sizeof(line), fp) && list.count < MAX_FILES) {
-        if (strlen(line) > 3) {
-            GitFile file;
-            file.status = parse_status(line);
-            strncpy(file.filename, line + 3, MAX_FILENAME - 1);
-            file.filename[strcspn(file.filename, "\n")] = '\0';
-            list.files[list.count++] = file;
+
+    // Porcelain paths are relative to the top of the work tree, the
+    // prefix is where the listed directory sits inside it
+    size_t prefix_len = 0;
+    const char* prefix_args[] = {"rev-parse", "--show-prefix", NULL};
+    char* prefix = run_git(path, prefix_args, &prefix_len);
+    if (!prefix) return list;
+    prefix[strcspn(prefix, "\n")] = '\0';
+    prefix_len = strlen(prefix);
+
+    size_t length = 0;
+    // List every untracked file, not just the untracked directory holding it
+    const char* status_args[] = {"status", "--porcelain", "-z", "--untracked-files=all", NULL};
+    char* output = run_git(path, status_args, &length);
+    if (!output) {
+        free(prefix);
+        return list;
+    }
+
+    // Each record is "XY path" terminated by NUL, renames and copies
+    // are followed by one more record with the original path
+    size_t pos = 0;
+    while (pos < length && list.count < MAX_FILES) {
+        const char* record = output + pos;
+        size_t record_len = strlen(record);
+        pos += record_len + 1;
+        if (record_len < 4) continue;
+
+        if (record[0] == 'R' || record[0] == 'C') {
+            pos += strlen(output + pos) + 1;  // Skip the original path, the listing shows the new name
         }
+
+        // Files outside the listed directory cannot match any entry
+        const char* name = record + 3;
+        if (strncmp(name, prefix, prefix_len) != 0) continue;
+        name += prefix_len;
+
+        GitFile file;
+        file.status = parse_status(record);
+        file.staged = record[0] != ' ' && record[0] != '?';
+        strncpy(file.filename, name, MAX_FILENAME - 1);
+        file.filename[MAX_FILENAME - 1] = '\0';
+        list.files[list.count++] = file;
     }
-    pclose(fp);
+
+    free(output);
+    free(prefix);
     return list;
 }
 
 char* get_current_branch(const char* path) {
-    char command[1024];
-    snprintf(command, sizeof(command), "git -C \"%s\" rev-parse --abbrev-ref HEAD 2>/dev/null", path);
-    
-    FILE* fp = popen(command, "r");
-    if (!fp) return NULL;
-
-    char* branch = malloc(256);
-    if (!fgets(branch, 256, fp)) {
+    const char* branch_args[] = {"rev-parse", "--abbrev-ref", "HEAD", NULL};
+    size_t length = 0;
+    char* branch = run_git(path, branch_args, &length);
+    if (!branch) return NULL;
+    branch[strcspn(branch, "\n")] = '\0';
+    if (branch[0] == '\0') {
         free(branch);
-        pclose(fp);
         return NULL;
     }
-    pclose(fp);
-    branch[strcspn(branch, "\n")] = '\0';
     return branch;
 }
@@ -22,6 +22,7 @@
 typedef struct {
     char filename[MAX_FILENAME];
     char status;
+    bool staged;  // The change is recorded in the index rather than only in the work tree
 } GitFile;
 
 typedef struct {
@@ -32,8 +33,9 @@ typedef struct {
 /**
  * @brief Checks if the current directory is part of a Git repository.
  *
- * This function determines whether the current working directory
- * is within a Git repository.
+ * This function determines whether the given directory is within a Git
+ * repository by looking for a .git entry in it and its parents, so no
+ * git process is started outside of repositories.
  *
  * @return true if the current directory is in a Git repository, false otherwise.
  */
@@ -43,7 +45,10 @@ bool is_git_repository(const char* path);
  * @brief Retrieves the Git status for all files in the current repository.
  *
  * This function gets the status of all files in the current Git repository,
- * including untracked, modified, and staged files.
+ * including untracked, modified, and staged files, from the stable
+ * `git status --porcelain -z` format. Names are relative to the given
+ * directory, and files outside of it are left out. Renamed files are
+ * reported under their new name.
  *
  * @return GitFileList structure containing the status of all files in the repository.
  */
@@ -18,12 +18,13 @@
 #include <time.h>
 #include <pwd.h>
 #include <grp.h>
-#include <locale.h>
 #include <errno.h>
 #include <unistd.h>
 #include <glob.h>
 #include "longlisting.h"
 #include "emoji_utils.h"
+#include "display_utils.h"
+#include "file_card.h"
 
 #define MAX_PATH 4096
 #define MAX_ENTRIES 1000
@@ -38,45 +39,27 @@ struct file_info {
     char full_path[MAX_PATH];   /**< Full path to the file or directory */
     off_t size;                 /**< Size of the file or directory */
     mode_t mode;                /**< File mode (permissions) */
+    mode_t target_mode;         /**< Mode of a symbolic link's target, 0 if none or unreachable */
     time_t mtime;               /**< Last modification time */
     int is_dir;                 /**< Flag indicating if it's a directory */
     int subdir_count;           /**< Number of subdirectories (for directories) */
     char owner[256];            /**< Owner of the file or directory */
     char user_rights[16];       /**< User rights represented as emojis */
+    char permissions[11];       /**< Permission string in ls format, e.g. drwxr-xr-x */
+    char link_target[MAX_PATH]; /**< Target of a symbolic link, empty otherwise */
     char time_ago[MAX_TIME_AGO_LEN]; /**< Human-readable time since last modification */
 };
 
 // Function prototypes
-static char *format_size(off_t size);
 static void format_time_ago(time_t file_time, char *buf, size_t buf_size);
 static void get_user_rights(mode_t mode, char *rights);
+static void format_permissions(mode_t mode, char *buf);
 static off_t get_dir_size(const char *path);
 static int count_subdirs(const char *path);
 static int compare_entries(const void *a, const void *b);
 static int get_file_info(const char *path, struct file_info *fi, size_t *max_owner_len, size_t *max_time_ago_len);
 static void print_sorted_entries(struct file_info *entries, int entry_count, size_t max_owner_len, size_t max_time_ago_len);
 
-/**
- * @brief Formats a file size into a human-readable string.
- *
- * @param size The size in bytes to format.
- * @return A pointer to a static buffer containing the formatted size string.
- */
-static char *format_size(off_t size) {
-    static char buf[64];
-    const char *units[] = {"B", "K", "M", "G", "T", "P", "E", "Z", "Y"};
-    int i = 0;
-    double dsize = size;
-
-    while (dsize >= 1024 && i < 8) {
-        dsize /= 1024;
-        i++;
-    }
-
-    snprintf(buf, sizeof(buf), "%.1f%s", dsize, units[i]);
-    return buf;
-}
-
 /**
  * @brief Formats the time difference between now and a given time into a human-readable string.
  *
@@ -126,6 +109,33 @@ static void get_user_rights(mode_t mode, char *rights) {
     strcat(rights, (mode & S_IXUSR) ? exec : no);
 }
 
+/**
+ * @brief Converts file mode into an ls-style permission string.
+ *
+ * @param mode The file mode containing the type and permissions.
+ * @param buf Buffer of at least 11 bytes to store the string (e.g. "drwxr-xr-x").
+ */
+static void format_permissions(mode_t mode, char *buf) {
+    if (S_ISDIR(mode)) buf[0] = 'd';
+    else if (S_ISLNK(mode)) buf[0] = 'l';
+    else if (S_ISCHR(mode)) buf[0] = 'c';
+    else if (S_ISBLK(mode)) buf[0] = 'b';
+    else if (S_ISFIFO(mode)) buf[0] = 'p';
+    else if (S_ISSOCK(mode)) buf[0] = 's';
+    else buf[0] = '-';
+
+    buf[1] = (mode & S_IRUSR) ? 'r' : '-';
+    buf[2] = (mode & S_IWUSR) ? 'w' : '-';
+    buf[3] = (mode & S_IXUSR) ? 'x' : '-';
+    buf[4] = (mode & S_IRGRP) ? 'r' : '-';
+    buf[5] = (mode & S_IWGRP) ? 'w' : '-';
+    buf[6] = (mode & S_IXGRP) ? 'x' : '-';
+    buf[7] = (mode & S_IROTH) ? 'r' : '-';
+    buf[8] = (mode & S_IWOTH) ? 'w' : '-';
+    buf[9] = (mode & S_IXOTH) ? 'x' : '-';
+    buf[10] = '\0';
+}
+
 /**
  * @brief Calculates the total size of a directory and its contents.
  *
@@ -264,6 +274,21 @@ static int get_file_info(const char *path, struct file_info *fi, size_t *max_own
     }
 
     get_user_rights(fi->mode, fi->user_rights);
+    format_permissions(fi->mode, fi->permissions);
+
+    // Remember where symbolic links point so the listing can show it
+    fi->link_target[0] = '\0';
+    fi->target_mode = 0;
+    if (S_ISLNK(st.st_mode)) {
+        struct stat target_st;
+        if (stat(path, &target_st) == 0) {
+            fi->target_mode = target_st.st_mode;
+        }
+        ssize_t len = readlink(path, fi->link_target, sizeof(fi->link_target) - 1);
+        if (len >= 0) {
+            fi->link_target[len] = '\0';
+        }
+    }
 
     return 0;
 }
@@ -279,22 +304,29 @@ static int get_file_info(const char *path, struct file_info *fi, size_t *max_own
 static void print_sorted_entries(struct file_info *entries, int entry_count, size_t max_owner_len, size_t max_time_ago_len) {
     for (int i = 0; i < entry_count; i++) {
         struct file_info *fi = &entries[i];
-        char *emoji = get_emoji(fi->full_path);
+        char *emoji = get_emoji(fi->full_path, fi->mode, fi->target_mode);
+        char *name = make_display_name(fi->name);
+        char *link_target = make_display_name(fi->link_target);
 
-        printf("%8s  %-*s  \xF0\x9F\x91\x91: %-*s \xF0\x9F\x93\x9C: %s  %s %-17s",
+        printf("%8s  %-*s  \xF0\x9F\x91\x91: %-*s \xF0\x9F\x93\x9C: %s %s  %s %-17s",
                format_size(fi->size),
                (int)max_time_ago_len, fi->time_ago,
                (int)max_owner_len, fi->owner,
                fi->user_rights,
+               fi->permissions,
                emoji,
-               fi->name);
+               name ? name : fi->name);
 
         if (fi->is_dir) {
             printf("(%d)", fi->subdir_count);
+        } else if (fi->link_target[0] != '\0') {
+            printf("→ %s", link_target ? link_target : fi->link_target);
         }
         printf("\n");
 
         free(emoji);
+        free(name);
+        free(link_target);
     }
 }
 
@@ -302,24 +334,33 @@ static void print_sorted_entries(struct file_info *entries, int entry_count, siz
  * @brief Prints a detailed listing of the contents of a directory.
  *
  * @param path The path of the directory to list.
+ * @param show_hidden Include entries whose names start with a dot.
+ * @return 0 on success, -1 if the directory or one of its entries cannot be read.
  */
-void print_longlisting(const char *path) {
-    setlocale(LC_ALL, "");
-
-    struct file_info entries[MAX_ENTRIES];
+int print_longlisting(const char *path, bool show_hidden) {
+    int status = 0;
     int entry_count = 0;
     size_t max_owner_len = 0;
     size_t max_time_ago_len = 0;
 
     DIR *dir = opendir(path);
     if (!dir) {
-        perror("Error opening directory");
-        return;
+        fprintf(stderr, "facad: cannot open '%s': %s\n", path, strerror(errno));
+        return -1;
+    }
+
+    // Entries are too large to keep MAX_ENTRIES of them on the stack
+    struct file_info *entries = malloc(MAX_ENTRIES * sizeof(struct file_info));
+    if (!entries) {
+        fprintf(stderr, "Memory allocation failed\n");
+        closedir(dir);
+        return -1;
     }
 
     struct dirent *entry;
     while ((entry = readdir(dir)) != NULL && entry_count < MAX_ENTRIES) {
         if (strcmp(entry->d_name, ".") == 0 || strcmp(entry->d_name, "..") == 0) continue;
+        if (!show_hidden && entry->d_name[0] == '.') continue;  // Hidden unless -A is given
 
         char full_path[MAX_PATH];
         snprintf(full_path, sizeof(full_path), "%s/%s", path, entry->d_name);
@@ -327,21 +368,42 @@ void print_longlisting(const char *path) {
         struct file_info *fi = &entries[entry_count];
         if (get_file_info(full_path, fi, &max_owner_len, &max_time_ago_len) == 0) {
             entry_count++;
+        } else {
+            // e.g. a directory that can be read but not searched
+            fprintf(stderr, "facad: cannot access '%s': %s\n", full_path, strerror(errno));
+            status = -1;
         }
     }
     closedir(dir);
 
     qsort(entries, entry_count, sizeof(struct file_info), compare_entries);
     print_sorted_entries(entries, entry_count, max_owner_len, max_time_ago_len);
+    free(entries);
+    return status;
 }
 
 
-void print_longlisting_files(const char **patterns, int pattern_count) {
-    struct file_info entries[MAX_ENTRIES];
+/**
+ * @brief Prints a detailed listing of files and glob pattern matches.
+ *
+ * Directories are described like files instead of being listed.
+ *
+ * @param patterns The file paths and glob patterns.
+ * @param pattern_count The number of paths and patterns.
+ * @return 0 on success, -1 if a pattern matches nothing or a file cannot be read.
+ */
+int print_longlisting_files(const char **patterns, int pattern_count) {
+    int status = 0;
     int entry_count = 0;
     size_t max_owner_len = 0;
     size_t max_time_ago_len = 0;
 
+    struct file_info *entries = malloc(MAX_ENTRIES * sizeof(struct file_info));
+    if (!entries) {
+        fprintf(stderr, "Memory allocation failed\n");
+        return -1;
+    }
+
     for (int i = 0; i < pattern_count; i++) {
         glob_t globbuf;
         int glob_result = glob(patterns[i], GLOB_TILDE, NULL, &globbuf);
@@ -351,10 +413,16 @@ void print_longlisting_files(const char **patterns, int pattern_count) {
                 struct file_info *fi = &entries[entry_count];
                 if (get_file_info(globbuf.gl_pathv[j], fi, &max_owner_len, &max_time_ago_len) == 0) {
                     entry_count++;
+                } else {
+                    fprintf(stderr, "facad: cannot access '%s': %s\n", globbuf.gl_pathv[j], strerror(errno));
+                    status = -1;
                 }
             }
         } else {
-            fprintf(stderr, "glob error for pattern '%s': %s\n", patterns[i], strerror(errno));
+            // Like ls, a pattern without matches is reported as a missing file
+            fprintf(stderr, "facad: cannot access '%s': %s\n", patterns[i],
+                    glob_result == GLOB_NOMATCH ? strerror(ENOENT) : strerror(errno));
+            status = -1;
         }
         globfree(&globbuf);
     }
@@ -362,7 +430,7 @@ void print_longlisting_files(const char **patterns, int pattern_count) {
     if (entry_count > 0) {
         qsort(entries, entry_count, sizeof(struct file_info), compare_entries);
         print_sorted_entries(entries, entry_count, max_owner_len, max_time_ago_len);
-    } else {
-        fprintf(stderr, "No matching files found.\n");
     }
+    free(entries);
+    return status;
 }
@@ -1,8 +1,10 @@
 #ifndef LONGLISTING_H
 #define LONGLISTING_H
 
-void print_longlisting(const char *path);
-void print_longlisting_files(const char **patterns, int pattern_count);
+#include <stdbool.h>
+
+int print_longlisting(const char *path, bool show_hidden);
+int print_longlisting_files(const char **patterns, int pattern_count);
 
 #endif // LONGLISTING_H
 
@@ -0,0 +1,191 @@
+/**
+ * @file ls_colors.c
+ * @brief Implementation of LS_COLORS parsing and lookup.
+ *
+ * This file contains a parser for the colon-separated `key=value` list
+ * in LS_COLORS and the lookup of an entry's color by type and extension.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#include <stdlib.h>
+#include <string.h>
+#include <strings.h>
+#include <sys/stat.h>
+
+#include "ls_colors.h"
+
+/**
+ * @enum LsColorType
+ * @brief Entry types that have their own LS_COLORS key.
+ */
+typedef enum {
+    LS_DIRECTORY,  // di
+    LS_SYMLINK,    // ln
+    LS_EXECUTABLE, // ex
+    LS_FILE,       // fi
+    LS_SOCKET,     // so
+    LS_PIPE,       // pi
+    LS_BLOCK,      // bd
+    LS_CHAR,       // cd
+    LS_TYPE_COUNT
+} LsColorType;
+
+static const char *type_keys[LS_TYPE_COUNT] = {"di", "ln", "ex", "fi", "so", "pi", "bd", "cd"};
+
+// Colors used by dircolors when LS_COLORS is not set
+static const char *default_type_colors[LS_TYPE_COUNT] = {
+    "01;34", "01;36", "01;32", NULL, "01;35", "40;33", "40;33;01", "40;33;01"
+};
+
+/**
+ * @struct LsColorExtension
+ * @brief Color for file names ending with a suffix.
+ */
+typedef struct {
+    const char *suffix; /**< Suffix including the dot, e.g. ".tar.gz" */
+    const char *color;  /**< SGR parameters */
+} LsColorExtension;
+
+static char *ls_colors_copy;                              // Parsed copy of LS_COLORS, owns all strings
+static int symlink_as_target;                             // Set by ln=target, links take their target's color
+static const char *type_colors[LS_TYPE_COUNT];            // Colors by entry type
+static LsColorExtension *extension_colors;                // Colors by suffix
+static size_t extension_count;                            // Number of suffix colors
+
+/**
+ * @brief Checks whether a color value means "no color".
+ *
+ * @param color The SGR parameters.
+ * @return 1 if the value is empty or resets all attributes, 0 otherwise.
+ */
+static int is_no_color(const char *color) {
+    return color[0] == '\0' || strcmp(color, "0") == 0 || strcmp(color, "00") == 0;
+}
+
+/**
+ * @brief Checks whether a color value is a list of SGR parameters.
+ *
+ * Only digits and semicolons are accepted, so nothing but a color can
+ * end up in the escape sequence.
+ *
+ * @param color The value from LS_COLORS.
+ * @return 1 if the value can be printed as SGR parameters, 0 otherwise.
+ */
+static int is_sgr_parameters(const char *color) {
+    return strspn(color, "0123456789;") == strlen(color);
+}
+
+/**
+ * @brief Parses the LS_COLORS environment variable.
+ */
+void load_ls_colors(void) {
+    // Start from the defaults of dircolors, keys in LS_COLORS override them
+    memcpy(type_colors, default_type_colors, sizeof(type_colors));
+
+    const char *ls_colors = getenv("LS_COLORS");
+    if (!ls_colors) {
+        return;  // Not configured, keep the defaults
+    }
+
+    ls_colors_copy = strdup(ls_colors);
+    if (!ls_colors_copy) return;
+
+    // Every pair contains a '=' so the string length bounds the number of pairs
+    extension_colors = malloc((strlen(ls_colors_copy) / 2 + 1) * sizeof(LsColorExtension));
+    if (!extension_colors) return;
+
+    char *saveptr = NULL;
+    for (char *pair = strtok_r(ls_colors_copy, ":", &saveptr); pair; pair = strtok_r(NULL, ":", &saveptr)) {
+        char *color = strchr(pair, '=');
+        if (!color) continue;  // Malformed pair
+        *color++ = '\0';
+
+        if (strcmp(pair, "ln") == 0 && strcmp(color, "target") == 0) {
+            symlink_as_target = 1;
+            continue;
+        }
+        if (!is_sgr_parameters(color)) continue;  // Unsupported value, keep the default
+
+        if (pair[0] == '*' && pair[1] != '\0') {
+            extension_colors[extension_count].suffix = pair + 1;
+            extension_colors[extension_count].color = is_no_color(color) ? NULL : color;
+            extension_count++;
+            continue;
+        }
+
+        for (int type = 0; type < LS_TYPE_COUNT; type++) {
+            if (strcmp(pair, type_keys[type]) == 0) {
+                type_colors[type] = is_no_color(color) ? NULL : color;
+                if (type == LS_SYMLINK) symlink_as_target = 0;  // The last ln key wins
+                break;
+            }
+        }
+    }
+}
+
+/**
+ * @brief Looks up the color of a regular file by its suffix.
+ *
+ * Later patterns override earlier ones, as in ls.
+ *
+ * @param name The file name.
+ * @return The SGR parameters, or NULL if no pattern matches.
+ */
+static const char *find_extension_color(const char *name) {
+    size_t name_len = strlen(name);
+    for (size_t i = extension_count; i-- > 0;) {
+        size_t suffix_len = strlen(extension_colors[i].suffix);
+        if (suffix_len <= name_len && strcasecmp(name + name_len - suffix_len, extension_colors[i].suffix) == 0) {
+            return extension_colors[i].color;
+        }
+    }
+    return NULL;
+}
+
+/**
+ * @brief Looks up the color of an entry.
+ *
+ * @param name The file name of the entry.
+ * @param mode The file type and permission bits, as reported by lstat.
+ * @param target_mode The mode of a symbolic link's target, 0 if it cannot be reached.
+ * @return The SGR parameters, or NULL if the entry is not colored.
+ */
+const char *find_ls_color(const char *name, mode_t mode, mode_t target_mode) {
+    if (S_ISDIR(mode)) return type_colors[LS_DIRECTORY];
+    if (S_ISLNK(mode)) {
+        // With ln=target, links look like what they point to; dangling
+        // links, and links to links, keep the default link color
+        if (symlink_as_target) {
+            return (target_mode != 0 && !S_ISLNK(target_mode)) ? find_ls_color(name, target_mode, 0)
+                                                                : default_type_colors[LS_SYMLINK];
+        }
+        return type_colors[LS_SYMLINK];
+    }
+    if (S_ISSOCK(mode)) return type_colors[LS_SOCKET];
+    if (S_ISFIFO(mode)) return type_colors[LS_PIPE];
+    if (S_ISBLK(mode)) return type_colors[LS_BLOCK];
+    if (S_ISCHR(mode)) return type_colors[LS_CHAR];
+
+    // Executables take precedence over extensions, as in ls
+    if (S_ISREG(mode) && (mode & (S_IXUSR | S_IXGRP | S_IXOTH)) && type_colors[LS_EXECUTABLE]) {
+        return type_colors[LS_EXECUTABLE];
+    }
+
+    const char *color = find_extension_color(name);
+    return color ? color : type_colors[LS_FILE];
+}
+
+/**
+ * @brief Frees the parsed LS_COLORS entries.
+ */
+void free_ls_colors(void) {
+    free(extension_colors);
+    free(ls_colors_copy);
+    extension_colors = NULL;
+    ls_colors_copy = NULL;
+    extension_count = 0;
+    symlink_as_target = 0;
+    memset(type_colors, 0, sizeof(type_colors));
+}
@@ -0,0 +1,58 @@
+/**
+ * @file ls_colors.h
+ * @brief File name colors taken from the LS_COLORS environment variable.
+ *
+ * This header file declares functions for parsing LS_COLORS, the variable
+ * set by dircolors(1) and used by ls(1), and for looking up the color of
+ * an entry by its type or extension. When LS_COLORS is not set, the
+ * defaults of dircolors are used.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#ifndef LS_COLORS_H
+#define LS_COLORS_H
+
+#include <sys/types.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/**
+ * @brief Parses the LS_COLORS environment variable.
+ *
+ * Type keys (di, ln, ex, fi, so, pi, bd, cd) and extension patterns
+ * (*.ext) are recognized; other keys are ignored. The defaults of
+ * dircolors apply to types without a key, and values other than SGR
+ * parameters (or ln=target) are ignored.
+ */
+void load_ls_colors(void);
+
+/**
+ * @brief Looks up the color of an entry.
+ *
+ * Directories, symbolic links and special files are colored by type.
+ * Executables use the ex color, and other regular files are matched
+ * against the extension patterns (compared case-insensitively). With
+ * ln=target, symbolic links are colored like their target, and links
+ * whose target cannot be reached get the default link color.
+ *
+ * @param name The file name of the entry.
+ * @param mode The file type and permission bits, as reported by lstat.
+ * @param target_mode The mode of a symbolic link's target, 0 if it cannot be reached.
+ * @return The SGR parameters (e.g. "01;34"), or NULL if the entry is not colored.
+ */
+const char *find_ls_color(const char *name, mode_t mode, mode_t target_mode);
+
+/**
+ * @brief Frees the parsed LS_COLORS entries.
+ */
+void free_ls_colors(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LS_COLORS_H */
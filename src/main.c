@@ -12,9 +12,11 @@
  */
 
 #include <stdio.h>
+#include <limits.h>
 #include <errno.h>
 #include <stdlib.h>
 #include <string.h>
+#include <strings.h>
 #include <unistd.h>
 #include <dirent.h>
 #include <fnmatch.h>
@@ -27,96 +29,25 @@
 #include "args_parser.h"
 #include "longlisting.h"
 #include "dir_analytics.h"
+#include "dir_listing.h"
 #include "display_utils.h"
+#include "emoji_config.h"
+#include "ls_colors.h"
 #include "dev_dir_utils.h"
 #include "git_integration.h"
 
 #define MAX_PATH 4096        // Maximum path length
-#define INITIAL_ENTRIES 512  // Initial number of directory entries to allocate
 
 /**
- * @brief Dynamically allocates or reallocates memory for directory entries.
- *
- * This function manages the memory for the array of FileCardInfo structures.
- * It starts with INITIAL_ENTRIES and doubles the size when more space is needed.
- *
- * @param entries Pointer to the array of FileCardInfo structures.
- * @param current_size Pointer to the current size of the array.
- * @return 0 on success, -1 on failure.
- */
-static int allocate_entries(FileCardInfo **entries, int *current_size) {
-    // Calculate new size (double the current size or use initial size if NULL)
-    int new_size = (*entries == NULL) ? INITIAL_ENTRIES : (*current_size * 2);
-
-    // Attempt to reallocate memory for entries
-    FileCardInfo *new_entries = realloc(*entries, new_size * sizeof(FileCardInfo));
-
-    // Check if reallocation was successful
-    if (new_entries == NULL) {
-        perror("Failed to allocate memory for entries"); // Print error message
-        return -1;  // Return -1 to indicate failure
-    }
-
-    // Update entries pointer and current size
-    *entries = new_entries;
-    *current_size = new_size;
-    return 0;  // Return 0 to indicate success
-}
-
-/**
- * @brief Processes directory entries and populates the entries array.
- *
- * This function reads directory contents, creates FileCardInfo structures for each entry,
- * and dynamically allocates memory as needed. It also integrates Git status information.
- *
- * @param dir_path Path to the directory to process.
- * @param entries Pointer to the array of FileCardInfo structures.
- * @param num_entries Pointer to the number of entries processed.
- * @param current_size Pointer to the current size of the entries array.
- * @return 0 on success, -1 on failure.
+ * @struct VisitedDirList
+ * @brief Device and inode numbers of directories already listed with -R.
  */
-static int process_directory(const char *dir_path, FileCardInfo **entries, int *num_entries, int *current_size) {
-    DIR *dir = opendir(dir_path); // Open the directory
-    if (dir == NULL) {
-        perror("Error opening directory"); // Print error message if directory can't be opened
-        return -1;
-    }
-
-    struct dirent *entry;
-    while ((entry = readdir(dir)) != NULL) { // Loop through each entry in the directory
-        // Skip '.' and '..' entries
-        if (strcmp(entry->d_name, ".") == 0 || strcmp(entry->d_name, "..") == 0) {
-            continue;
-        }
-
-        // Check if we need to allocate more memory
-        if (*num_entries >= *current_size) {
-            if (allocate_entries(entries, current_size) != 0) {
-                closedir(dir); // Close the directory before returning
-                return -1;
-            }
-        }
-
-        // Construct full path for the current entry
-        char full_path[MAX_PATH];
-        snprintf(full_path, sizeof(full_path), "%s/%s", dir_path, entry->d_name);
-
-        // Create a file entry and add it to the array
-        if (create_file_entry(&(*entries)[*num_entries], entry->d_name) != 0) {
-            fprintf(stderr, "Warning: Unable to get info for %s: %s\n", full_path, strerror(errno));
-            continue;
-        }
-
-        (*num_entries)++; // Increment the number of entries
-    }
-
-    closedir(dir); // Close the directory
-
-    // Integrate Git status information for the entries
-    integrate_git_status(entries, num_entries, dir_path);
-
-    return 0; // Return 0 to indicate success
-}
+typedef struct {
+    dev_t *devices;  /**< Device numbers of visited directories */
+    ino_t *inodes;   /**< Inode numbers of visited directories */
+    int count;       /**< Number of visited directories */
+    int size;        /**< Allocated capacity of both arrays */
+} VisitedDirList;
 
 /**
  * @brief Processes files or patterns and populates the entries array.
@@ -126,27 +57,34 @@ static int process_directory(const char *dir_path, FileCardInfo **entries, int *
  *
  * @param patterns Array of file patterns or names to process.
  * @param pattern_count Number of patterns in the array.
+ * @param show_hidden Let wildcards match a leading dot, as with ls -A.
  * @param entries Pointer to the array of FileCardInfo structures.
  * @param num_entries Pointer to the number of entries processed.
  * @param current_size Pointer to the current size of the entries array.
  * @return 0 on success, -1 on failure.
  */
-static int process_files_or_patterns(const char **patterns, int pattern_count, FileCardInfo **entries, int *num_entries, int *current_size) {
+static int process_files_or_patterns(const char **patterns, int pattern_count, bool show_hidden, FileCardInfo **entries, int *num_entries, int *current_size) {
+    int match_flags = show_hidden ? 0 : FNM_PERIOD; // Like the shell, '*' skips dotfiles by default
     DIR *dir;
     struct dirent *entry;
     char full_path[MAX_PATH];
 
     dir = opendir("."); // Open the current directory
     if (dir == NULL) {
-        perror("Error opening current directory"); // Print error message if directory can't be opened
+        fprintf(stderr, "facad: cannot open '.': %s\n", strerror(errno));
         return -1;
     }
 
     while ((entry = readdir(dir)) != NULL) { // Loop through each entry in the directory
+        // Never list '.' and '..', even when a pattern such as '.*' matches them
+        if (strcmp(entry->d_name, ".") == 0 || strcmp(entry->d_name, "..") == 0) {
+            continue;
+        }
+
         bool match = false;
         // Check if the entry matches any of the provided patterns
         for (int i = 0; i < pattern_count; i++) {
-            if (fnmatch(patterns[i], entry->d_name, 0) == 0) {
+            if (fnmatch(patterns[i], entry->d_name, match_flags) == 0) {
                 match = true;
                 break;
             }
@@ -164,8 +102,10 @@ static int process_files_or_patterns(const char **patterns, int pattern_count, F
             // Create a file entry for the matching file
             snprintf(full_path, sizeof(full_path), "%s", entry->d_name);
             if (create_file_entry(&(*entries)[*num_entries], full_path) != 0) {
-                fprintf(stderr, "Warning: Unable to get info for %s: %s\n", full_path, strerror(errno));
-                continue;
+                fprintf(stderr, "facad: cannot access '%s': %s\n", full_path, strerror(errno));
+                if (create_unknown_file_entry(&(*entries)[*num_entries], full_path) != 0) {
+                    continue;
+                }
             }
 
             (*num_entries)++; // Increment the number of entries
@@ -189,6 +129,31 @@ static int is_directory(const char *path) {
     return S_ISDIR(statbuf.st_mode); // Return 1 if it's a directory, 0 otherwise
 }
 
+/**
+ * @brief Checks whether the locale uses UTF-8, as set in the environment.
+ *
+ * The variables are consulted in the order the C library uses them:
+ * LC_ALL, then LC_CTYPE, then LANG.
+ *
+ * @return 1 if the effective locale name mentions UTF-8, 0 otherwise.
+ */
+static int is_utf8_locale(void) {
+    const char *names[] = {"LC_ALL", "LC_CTYPE", "LANG"};
+    for (size_t i = 0; i < sizeof(names) / sizeof(names[0]); i++) {
+        const char *value = getenv(names[i]);
+        if (value && value[0] != '\0') {
+            // Match the codeset case-insensitively, e.g. en_US.UTF-8 or C.utf8
+            for (const char *p = value; *p; p++) {
+                if (strncasecmp(p, "UTF-8", 5) == 0 || strncasecmp(p, "UTF8", 4) == 0) {
+                    return 1;
+                }
+            }
+            return 0;
+        }
+    }
+    return 0;
+}
+
 /**
  * @brief Processes a single target (directory, file, or pattern).
  *
@@ -196,36 +161,21 @@ static int is_directory(const char *path) {
  * and individual files. It populates the entries array accordingly.
  *
  * @param target The target to process (directory path, file path, or pattern).
+ * @param show_hidden Include hidden entries of directories and patterns.
+ * @param directory_itself Add a directory as a single entry instead of its contents.
  * @param entries Pointer to the array of FileCardInfo structures.
  * @param num_entries Pointer to the number of entries processed.
  * @param current_size Pointer to the current size of the entries array.
  * @return 0 on success, -1 on failure.
  */
-static int process_target(const char *target, FileCardInfo **entries, int *num_entries, int *current_size) {
-    if (is_directory(target)) { // If the target is a directory
-        char previous_dir[MAX_PATH];
-        if (getcwd(previous_dir, sizeof(previous_dir)) == NULL) { // Get current working directory
-            perror("getcwd() error");
-            return -1;
-        }
-
-        if (chdir(target) != 0) { // Change to the target directory
-            fprintf(stderr, "Error changing to directory '%s': %s\n", target, strerror(errno));
-            return -1;
-        }
-
-        int result = process_directory(".", entries, num_entries, current_size); // Process the directory
-
-        // Change back to the previous directory
-        if (chdir(previous_dir) != 0) {
-            fprintf(stderr, "Error changing back to previous directory: %s\n", strerror(errno));
-            return -1;
-        }
-
-        return result;
+static int process_target(const char *target, bool show_hidden, bool directory_itself,
+                          FileCardInfo **entries, int *num_entries, int *current_size) {
+    if (!directory_itself && is_directory(target)) { // If the target is a directory to read
+        // Errors name the target as given, e.g. "cannot open 'foo'"
+        return list_directory(target, show_hidden, entries, num_entries, current_size);
     } else if (is_glob_pattern(target)) { // If the target is a glob pattern
-        return process_files_or_patterns(&target, 1, entries, num_entries, current_size);
-    } else { // Assume it's a single file
+        return process_files_or_patterns(&target, 1, show_hidden, entries, num_entries, current_size);
+    } else { // Assume it's a single file, or a directory listed as one entry
         // Check if we need to allocate more memory
         if (*num_entries >= *current_size) {
             if (allocate_entries(entries, current_size) != 0) {
@@ -234,7 +184,7 @@ static int process_target(const char *target, FileCardInfo **entries, int *num_e
         }
         // Create a file entry for the single file
         if (create_file_entry(&(*entries)[*num_entries], target) != 0) {
-            fprintf(stderr, "Unable to get info for %s: %s\n", target, strerror(errno));
+            fprintf(stderr, "facad: cannot access '%s': %s\n", target, strerror(errno));
             return -1;
         }
         (*num_entries)++; // Increment the number of entries
@@ -242,6 +192,23 @@ static int process_target(const char *target, FileCardInfo **entries, int *num_e
     return 0; // Return 0 to indicate success
 }
 
+/**
+ * @brief Checks that a target exists, reporting it on stderr if not.
+ *
+ * Glob patterns are not checked, their matches are examined later.
+ *
+ * @param target The target given on the command line.
+ * @return 1 if the target exists or is a pattern, 0 otherwise.
+ */
+static int target_exists(const char *target) {
+    struct stat target_stat;
+    if (!is_glob_pattern(target) && lstat(target, &target_stat) != 0) {
+        fprintf(stderr, "facad: cannot access '%s': %s\n", target, strerror(errno));
+        return 0;
+    }
+    return 1;
+}
+
 /**
  * @brief Checks if the targets are glob patterns or specific files.
  *
@@ -261,6 +228,355 @@ static int is_glob_or_specific_files(const char **targets, int target_count) {
     return 0; // Return 0 if all targets are directories
 }
 
+/**
+ * @brief Drops the entries whose names do not match the --filter pattern.
+ *
+ * Hidden entries are matched like any other, so "*.log" also keeps
+ * ".old.log" when -A is given. When listing recursively or as a tree,
+ * directories are kept so that their contents can still be listed.
+ *
+ * @param entries Array of FileCardInfo structures to filter in place.
+ * @param num_entries Pointer to the number of entries, updated after filtering.
+ * @param args Parsed command-line arguments with the pattern.
+ */
+static void filter_entries(FileCardInfo *entries, int *num_entries, const CommandLineArgs *args) {
+    if (!args->filter) {
+        return;
+    }
+
+    int keep_directories = (args->recursive || args->tree) && !args->directory_itself;
+    int kept = 0;
+    for (int i = 0; i < *num_entries; i++) {
+        // Match the file name only, targets given as paths keep their directory part
+        const char *base = strrchr(entries[i].name, '/');
+        base = base ? base + 1 : entries[i].name;
+
+        if ((keep_directories && entries[i].is_directory) || fnmatch(args->filter, base, 0) == 0) {
+            entries[kept++] = entries[i];
+        } else {
+            free_file_entry(&entries[i]);
+        }
+    }
+    *num_entries = kept;
+}
+
+/**
+ * @brief Checks whether the last entry names a path listed before it.
+ *
+ * Targets can name one file twice, as in "facad main.c ./main.c", which
+ * should list it once. Only the paths are compared, so hard links and
+ * names differing in case are still listed separately.
+ *
+ * @param entries Array of FileCardInfo structures.
+ * @param num_entries Number of entries, the last one being the new entry.
+ * @return 1 if an earlier entry has the same path, 0 otherwise.
+ */
+static int is_repeated_entry(const FileCardInfo *entries, int num_entries) {
+    for (int i = 0; i < num_entries - 1; i++) {
+        if (file_entries_equal(&entries[i], &entries[num_entries - 1])) {
+            return 1;
+        }
+    }
+    return 0;
+}
+
+/**
+ * @brief Sorts entries according to the command-line options.
+ *
+ * @param entries Array of FileCardInfo structures to sort.
+ * @param num_entries Number of entries in the array.
+ * @param args Parsed command-line arguments selecting the order.
+ */
+static void sort_entries(FileCardInfo *entries, int num_entries, const CommandLineArgs *args) {
+    FileCardComparator compare = NULL;
+    SortOptions sort_options = {.group_directories_first = args->group_directories_first};
+
+    // Pick the comparator for the requested sort key
+    switch (args->sort_key) {
+        case SORT_EXTENSION: compare = compare_file_entries; break;
+        case SORT_NAME:      compare = compare_file_entries_by_name; break;
+        case SORT_SIZE:      compare = compare_file_entries_by_size; break;
+        case SORT_TIME:      compare = compare_file_entries_by_time; break;
+        case SORT_VERSION:   compare = compare_file_entries_by_version; break;
+        case SORT_NONE:      break;  // Keep the order entries were read in
+    }
+
+    if (compare && sort_file_entries(entries, num_entries, compare, &sort_options) != 0) {
+        fprintf(stderr, "facad: not enough memory to sort entries\n");
+    }
+
+    // Reverse the sorted order if requested
+    if (args->reverse_sort) {
+        for (int i = 0, j = num_entries - 1; i < j; i++, j--) {
+            FileCardInfo tmp = entries[i];
+            entries[i] = entries[j];
+            entries[j] = tmp;
+        }
+    }
+}
+
+/**
+ * @brief Records a directory as visited during a recursive listing.
+ *
+ * Directories are identified by device and inode, so the same directory
+ * reached through a different path (e.g. a bind mount) is detected.
+ *
+ * @param visited List of directories visited so far.
+ * @param path Path of the directory to record.
+ * @return 0 if the directory is new, 1 if it was already visited or cannot be recorded.
+ */
+static int mark_visited(VisitedDirList *visited, const char *path) {
+    struct stat st;
+    if (stat(path, &st) != 0) {
+        return 1;
+    }
+
+    for (int i = 0; i < visited->count; i++) {
+        if (visited->devices[i] == st.st_dev && visited->inodes[i] == st.st_ino) {
+            return 1;
+        }
+    }
+
+    // Grow both arrays together when they are full
+    if (visited->count >= visited->size) {
+        int new_size = visited->size ? visited->size * 2 : 64;
+        dev_t *devices = realloc(visited->devices, new_size * sizeof(dev_t));
+        if (!devices) return 1;
+        visited->devices = devices;
+        ino_t *inodes = realloc(visited->inodes, new_size * sizeof(ino_t));
+        if (!inodes) return 1;
+        visited->inodes = inodes;
+        visited->size = new_size;
+    }
+
+    visited->devices[visited->count] = st.st_dev;
+    visited->inodes[visited->count] = st.st_ino;
+    visited->count++;
+    return 0;
+}
+
+static int list_directory_block(const char *path, const CommandLineArgs *args, int term_width,
+                                const DisplayOptions *options, VisitedDirList *visited, bool first_block);
+
+/**
+ * @brief Lists every subdirectory found among the given entries.
+ *
+ * Only real directories are descended into; symbolic links to directories
+ * are shown but not followed.
+ *
+ * @param parent Path of the directory the entries belong to.
+ * @param entries Sorted entries of the parent directory.
+ * @param num_entries Number of entries in the array.
+ * @param args Parsed command-line arguments.
+ * @param term_width Width of the terminal.
+ * @param options Display settings for each directory block.
+ * @param visited List of directories visited so far.
+ */
+static void list_subdirectories(const char *parent, FileCardInfo *entries, int num_entries,
+                                const CommandLineArgs *args, int term_width,
+                                const DisplayOptions *options, VisitedDirList *visited) {
+    for (int i = 0; i < num_entries; i++) {
+        if (!entries[i].is_directory) {
+            continue;
+        }
+
+        char path[MAX_PATH];
+        snprintf(path, sizeof(path), "%s/%s", parent, entries[i].name);
+        list_directory_block(path, args, term_width, options, visited, false);
+    }
+}
+
+/**
+ * @brief Lists a directory under a "path:" header.
+ *
+ * With -R its subdirectories are listed after it. Directories that cannot
+ * be read are reported and skipped, and directories that were already
+ * listed are not entered again.
+ *
+ * @param path Path of the directory, relative to the working directory.
+ * @param args Parsed command-line arguments.
+ * @param term_width Width of the terminal.
+ * @param options Display settings for each directory block.
+ * @param visited List of directories visited so far.
+ * @param first_block Whether nothing was printed before, so no blank line is needed.
+ * @return 0 if the directory was listed, -1 otherwise.
+ */
+static int list_directory_block(const char *path, const CommandLineArgs *args, int term_width,
+                                const DisplayOptions *options, VisitedDirList *visited, bool first_block) {
+    if (mark_visited(visited, path)) {
+        fprintf(stderr, "facad: skipping '%s': directory already listed\n", path);
+        return -1;
+    }
+
+    // Check readability first so the warning names the real path
+    DIR *dir = opendir(path);
+    if (dir == NULL) {
+        fprintf(stderr, "facad: cannot open '%s': %s\n", path, strerror(errno));
+        return -1;
+    }
+    closedir(dir);
+
+    FileCardInfo *entries = NULL;
+    int num_entries = 0;
+    int current_size = 0;
+    int result = list_directory(path, args->show_all, &entries, &num_entries, &current_size);
+    filter_entries(entries, &num_entries, args);
+
+    printf("%s%s%s:%s\n", first_block ? "" : "\n", ansi_style(ANSI_BOLD), path, ansi_style(ANSI_RESET));
+    if (result == 0 && num_entries > 0) {
+        sort_entries(entries, num_entries, args);
+        display_entries(entries, num_entries, term_width, path, 0, options);
+        if (args->show_totals) {
+            display_summary(entries, num_entries, options);
+        }
+        if (args->recursive) {
+            list_subdirectories(path, entries, num_entries, args, term_width, options, visited);
+        }
+    }
+
+    free_entries(entries, num_entries);
+    return result == 0 ? 0 : -1;
+}
+
+/**
+ * @brief Lists several targets the way ls does.
+ *
+ * Files and glob patterns are listed together first, followed by one
+ * block per directory. Targets that cannot be accessed are reported and
+ * skipped without stopping the rest of the listing.
+ *
+ * @param args Parsed command-line arguments.
+ * @param term_width Width of the terminal.
+ * @param options Display settings for each block.
+ * @return EXIT_SUCCESS if every target was listed, EXIT_FAILURE otherwise.
+ */
+static int list_multiple_targets(const CommandLineArgs *args, int term_width, const DisplayOptions *options) {
+    FileCardInfo *entries = NULL;
+    int num_entries = 0;
+    int current_size = 0;
+    int had_error = 0;
+
+    // Collect the plain files first, directories are listed afterwards
+    for (int i = 0; i < args->target_count; i++) {
+        const char *target = args->targets[i];
+        if (!target_exists(target)) {
+            had_error = 1;
+            continue;
+        }
+        if (is_glob_pattern(target) || !is_directory(target) || args->directory_itself) {
+            if (process_target(target, args->show_all, args->directory_itself, &entries, &num_entries, &current_size) != 0) {
+                had_error = 1;
+            } else if (!is_glob_pattern(target) && is_repeated_entry(entries, num_entries)) {
+                free_file_entry(&entries[--num_entries]);  // Named by an earlier argument
+            }
+        }
+    }
+
+    filter_entries(entries, &num_entries, args);
+
+    int printed = 0; // Whether a block was printed, so the next one is separated
+    if (num_entries > 0) {
+        sort_entries(entries, num_entries, args);
+        display_entries(entries, num_entries, term_width, ".", 0, options);
+        if (args->show_totals) {
+            display_summary(entries, num_entries, options);
+        }
+        printed = 1;
+    }
+
+    free_entries(entries, num_entries);
+
+    // Then list each directory under its own header
+    for (int i = 0; i < args->target_count; i++) {
+        const char *target = args->targets[i];
+        if (is_glob_pattern(target) || !is_directory(target) || args->directory_itself) {
+            continue;
+        }
+
+        VisitedDirList visited = {0};
+        if (list_directory_block(target, args, term_width, options, &visited, !printed) != 0) {
+            had_error = 1;
+        }
+        free(visited.devices);
+        free(visited.inodes);
+        printed = 1;
+    }
+
+    return had_error ? EXIT_FAILURE : EXIT_SUCCESS;
+}
+
+/**
+ * @brief Prints the entries of a directory as branches of a tree.
+ *
+ * Subdirectories are printed below their entry, indented with connector
+ * lines, until the depth limit of --level is reached. Symbolic links to
+ * directories are not followed, and directories that were already printed
+ * are marked instead of being entered again.
+ *
+ * @param path Path of the directory, relative to the working directory.
+ * @param prefix Connector lines of the enclosing levels.
+ * @param depth Depth of the entries of this directory, starting at 1.
+ * @param args Parsed command-line arguments.
+ * @param options Display settings for each entry.
+ * @param visited List of directories visited so far.
+ * @return 0 on success, -1 if a directory could not be read.
+ */
+static int print_tree_level(const char *path, const char *prefix, int depth, const CommandLineArgs *args,
+                            const DisplayOptions *options, VisitedDirList *visited) {
+    FileCardInfo *entries = NULL;
+    int num_entries = 0;
+    int current_size = 0;
+    int had_error = list_directory(path, args->show_all, &entries, &num_entries, &current_size) != 0;
+    filter_entries(entries, &num_entries, args);
+
+    sort_entries(entries, num_entries, args);
+
+    for (int i = 0; i < num_entries; i++) {
+        int last = (i == num_entries - 1);
+        printf("%s%s", prefix, last ? "└── " : "├── ");
+        write_entry(stdout, &entries[i], strlen(entries[i].display_name), options);
+
+        char child_path[MAX_PATH];
+        snprintf(child_path, sizeof(child_path), "%s/%s", path, entries[i].name);
+        int descend = entries[i].is_directory && (args->tree_depth == 0 || depth < args->tree_depth);
+        if (descend && mark_visited(visited, child_path)) {
+            printf("  [already listed]");  // Cut off loops, e.g. through bind mounts
+            descend = 0;
+        }
+        printf("\n");
+
+        if (descend) {
+            char child_prefix[MAX_PATH];
+            snprintf(child_prefix, sizeof(child_prefix), "%s%s", prefix, last ? "    " : "│   ");
+            if (print_tree_level(child_path, child_prefix, depth + 1, args, options, visited) != 0) {
+                had_error = 1;
+            }
+        }
+    }
+
+    free_entries(entries, num_entries);
+    return had_error ? -1 : 0;
+}
+
+/**
+ * @brief Prints a directory and everything below it as a tree.
+ *
+ * @param root Path of the directory at the root of the tree.
+ * @param args Parsed command-line arguments.
+ * @param options Display settings for each entry.
+ * @return 0 on success, -1 if a directory could not be read.
+ */
+static int print_tree(const char *root, const CommandLineArgs *args, const DisplayOptions *options) {
+    printf("%s%s%s\n", ansi_style(ANSI_BOLD), root, ansi_style(ANSI_RESET));
+
+    VisitedDirList visited = {0};
+    mark_visited(&visited, root);
+    int result = print_tree_level(root, "", 1, args, options, &visited);
+    free(visited.devices);
+    free(visited.inodes);
+    return result;
+}
+
 /**
  * @brief Main function of the facad program.
  *
@@ -297,36 +613,138 @@ int main(int argc, char *argv[]) {
         return EXIT_FAILURE;
     }
 
+    // Handle invalid option values
+    if (args.invalid_arg) {
+        fprintf(stderr, "%s: invalid argument '%s' for '%s'\n", argv[0], args.invalid_arg, args.invalid_arg_opt);
+        if (strcmp(args.invalid_arg_opt, "--sort") == 0) {
+            fprintf(stderr, "Valid arguments are: 'ext', 'name', 'size', 'time', 'version', 'none'\n");
+        } else if (strcmp(args.invalid_arg_opt, "--icons") == 0) {
+            fprintf(stderr, "Valid arguments are: 'always', 'auto', 'never'\n");
+        } else if (strcmp(args.invalid_arg_opt, "--group-directories-first") == 0) {
+            fprintf(stderr, "Valid arguments are: 'true', 'false'\n");
+        }
+        fprintf(stderr, "Try '%s --help' for more information.\n", argv[0]);
+        free_args(&args);
+        return EXIT_FAILURE;
+    }
+
     // Get current working directory
     char current_dir[MAX_PATH];
     if (getcwd(current_dir, sizeof(current_dir)) == NULL) {
-        perror("getcwd() error");
+        fprintf(stderr, "facad: cannot determine current directory: %s\n", strerror(errno));
         free_args(&args);
         return EXIT_FAILURE;
     }
 
-    // Handle long listing request
-    if (args.show_longlisting) {
-        if (args.target_count > 0 && is_glob_or_specific_files(args.targets, args.target_count)) {
-            print_longlisting_files(args.targets, args.target_count);
+    // Load user emoji mappings, if a configuration file exists
+    load_emoji_config();
+
+    // Load file name colors from LS_COLORS
+    load_ls_colors();
+
+    // Handle long listing and analytics requests, for the targets that exist
+    if (args.show_longlisting || args.show_dir_analytics) {
+        int had_error = 0;
+        const char **targets = malloc((args.target_count + 1) * sizeof(const char *));
+        int target_count = 0;
+        if (!targets) {
+            fprintf(stderr, "facad: not enough memory for the targets\n");
+            free_emoji_config();
+            free_ls_colors();
+            free_args(&args);
+            return EXIT_FAILURE;
+        }
+        for (int i = 0; i < args.target_count; i++) {
+            if (target_exists(args.targets[i])) {
+                targets[target_count++] = args.targets[i];
+            } else {
+                had_error = 1;
+            }
+        }
+        if (args.target_count == 0) {
+            targets[target_count++] = ".";
+        }
+
+        if (target_count == 0) {
+            // Every target was missing and has been reported
+        } else if (args.show_longlisting) {
+            // With -d, directories are described like files instead of listed
+            if (args.directory_itself || is_glob_or_specific_files(targets, target_count)) {
+                had_error |= print_longlisting_files(targets, target_count) != 0;
+            } else {
+                had_error |= print_longlisting(targets[0], args.show_all) != 0;
+            }
         } else {
-            print_longlisting(args.target_count > 0 ? args.targets[0] : ".");
+            had_error |= print_dir_analytics(targets[0]) != 0;
         }
+
+        free(targets);
+        free_emoji_config();
+        free_ls_colors();
         free_args(&args);
-        return EXIT_SUCCESS;
+        return had_error ? EXIT_FAILURE : EXIT_SUCCESS;
+    }
+
+    // When piped, print one plain name per line unless the grid is forced with -C
+    int one_per_line = !isatty(STDOUT_FILENO) && !args.force_columns;
+
+    // Get terminal width for display formatting, falling back to 80 columns
+    struct winsize w;
+    int term_width = 80;
+    if (args.width > 0) {
+        term_width = args.width;  // Fixed width requested with --width
+    } else if (args.width == 0) {
+        term_width = INT_MAX;  // No limit, everything fits on one row
+    } else if (ioctl(STDOUT_FILENO, TIOCGWINSZ, &w) == 0 && w.ws_col > 0) {
+        term_width = w.ws_col;
     }
 
-    // Handle directory analytics request
-    if (args.show_dir_analytics) {
-        print_dir_analytics(args.target_count > 0 ? args.targets[0] : ".");
+    // Decide once whether emojis are printed
+    int show_icons = args.icons == ICONS_ALWAYS || (args.icons == ICONS_DEFAULT && !one_per_line) ||
+                     (args.icons == ICONS_AUTO && !one_per_line && isatty(STDOUT_FILENO) && is_utf8_locale());
+
+    DisplayOptions display_options = {
+        .max_columns = one_per_line ? 1 : args.max_columns,
+        .plain = !show_icons,
+        .hide_git_status = one_per_line,
+        .across = args.across,
+        .group_by_emoji = args.emoji_summary,
+        .truncate_names = !one_per_line && !args.no_truncate,
+        .color = ansi_enabled(),
+    };
+
+    // Print directories as trees instead of grids
+    if (args.tree && !args.json && !args.directory_itself) {
+        int status = EXIT_SUCCESS;
+        int count = args.target_count > 0 ? args.target_count : 1;
+        for (int i = 0; i < count; i++) {
+            const char *root = args.target_count > 0 ? args.targets[i] : ".";
+            if (!is_directory(root)) {
+                fprintf(stderr, "facad: cannot list '%s' as a tree: not a directory\n", root);
+                status = EXIT_FAILURE;
+                continue;
+            }
+            if (i > 0) {
+                printf("\n");  // Separate the trees of several targets
+            }
+            if (print_tree(root, &args, &display_options) != 0) {
+                status = EXIT_FAILURE;
+            }
+        }
+        free_emoji_config();
+        free_ls_colors();
         free_args(&args);
-        return EXIT_SUCCESS;
+        return status;
     }
 
-    // Get terminal width for display formatting
-    struct winsize w;
-    ioctl(STDOUT_FILENO, TIOCGWINSZ, &w);
-    int term_width = w.ws_col;
+    // Several targets are listed in separate blocks, like ls does
+    if (args.target_count > 1 && !args.json) {
+        int status = list_multiple_targets(&args, term_width, &display_options);
+        free_emoji_config();
+        free_ls_colors();
+        free_args(&args);
+        return status;
+    }
 
     // Initialize variables for storing file entries
     FileCardInfo *entries = NULL;
@@ -338,11 +756,19 @@ int main(int argc, char *argv[]) {
     strcpy(display_path, current_dir);
 
     int show_path = 1; // Flag to determine if path should be displayed
+    int had_error = 0; // Flag set when a target could not be listed
 
     // Process targets if provided, otherwise process current directory
     if (args.target_count > 0) {
-        show_path = 0; // Don't show path for specific targets
+        // A directory argument gets a header just like the current directory
+        show_path = is_directory(args.targets[0]) && !args.directory_itself;
         for (int i = 0; i < args.target_count; i++) {
+            // Report missing targets up front and go on with the others
+            if (!target_exists(args.targets[i])) {
+                had_error = 1;
+                continue;
+            }
+
             // Get real path if possible
             char *real_path = realpath(args.targets[i], NULL);
             if (real_path != NULL) {
@@ -353,58 +779,94 @@ int main(int argc, char *argv[]) {
             }
 
             // Process the target
-            if (process_target(args.targets[i], &entries, &num_entries, &current_size) != 0) {
-                fprintf(stderr, "Error processing '%s'\n", args.targets[i]);
-                goto cleanup; // Jump to cleanup if there's an error
+            if (process_target(args.targets[i], args.show_all, args.directory_itself,
+                               &entries, &num_entries, &current_size) != 0) {
+                had_error = 1;
+                continue; // The failure was reported, list the remaining targets
             }
         }
+    } else if (args.directory_itself) {
+        // List the current directory itself as '.', like ls -d
+        show_path = 0;
+        if (process_target(".", args.show_all, true, &entries, &num_entries, &current_size) != 0) {
+            had_error = 1;
+            goto cleanup;
+        }
     } else {
         // Check if current directory is /dev and handle accordingly
         if (is_dev_directory(current_dir)) {
             strcpy(display_path, "/dev");
-            if (handle_dev_directory(&entries, &num_entries, &current_size) != 0) {
-                fprintf(stderr, "Error processing /dev directory\n");
+            if (handle_dev_directory(args.show_all, &entries, &num_entries, &current_size) != 0) {
+                had_error = 1;
                 goto cleanup;
             }
         } else {
             // Process current directory
-            if (process_directory(".", &entries, &num_entries, &current_size) != 0) {
-                fprintf(stderr, "Error processing current directory\n");
+            if (list_directory(".", args.show_all, &entries, &num_entries, &current_size) != 0) {
+                had_error = 1;
                 goto cleanup;
             }
         }
     }
 
+    if (one_per_line) {
+        show_path = 0; // A header line would be mistaken for a file name
+    }
+
+    // Keep only the entries matching --filter
+    filter_entries(entries, &num_entries, &args);
+
+    // Print machine-readable output instead of the grid
+    if (args.json) {
+        sort_entries(entries, num_entries, &args);
+        display_json(entries, num_entries);
+        goto cleanup;
+    }
+
     // Handle case when no entries are found
     if (num_entries == 0) {
-        char current_dir[MAX_PATH];
-        if (getcwd(current_dir, sizeof(current_dir)) != NULL) {
-            // Display current directory if it matches the display path
-            if (strcmp(current_dir, display_path) == 0) {
-                printf("\033[1m%s\033[0m\n", current_dir);
-            }
-            fprintf(stderr, "🚫 No files found\n");
-        } else {
-            fprintf(stderr, "🚫 No files found\n");
+        // Display the listed directory even when it is empty
+        if (show_path) {
+            printf("%s%s%s\n", ansi_style(ANSI_BOLD), display_path, ansi_style(ANSI_RESET));
+        }
+        if (!had_error) {
+            fprintf(stderr, "🚫 No files found\n");  // Failed targets were already reported
         }
     } else {
-        // Sort the entries alphabetically
-        qsort(entries, num_entries, sizeof(FileCardInfo), compare_file_entries);
+        sort_entries(entries, num_entries, &args);
 
+        // Display the entries using the appropriate format
+        display_entries(entries, num_entries, term_width, display_path, show_path, &display_options);
 
+        // Print the summary footer if requested
+        if (args.show_totals) {
+            display_summary(entries, num_entries, &display_options);
+        }
 
-        // Display the entries using the appropriate format
-        display_entries(entries, num_entries, term_width, display_path, show_path);
+        // Descend into subdirectories when a single directory was listed with -R
+        const char *listed_dir = NULL;
+        if (args.target_count == 0) {
+            listed_dir = is_dev_directory(current_dir) ? "/dev" : ".";
+        } else if (args.target_count == 1 && is_directory(args.targets[0])) {
+            listed_dir = args.targets[0];
+        }
+
+        if (args.recursive && listed_dir && !args.directory_itself) {
+            VisitedDirList visited = {0};
+            mark_visited(&visited, listed_dir);
+            list_subdirectories(listed_dir, entries, num_entries, &args, term_width, &display_options, &visited);
+            free(visited.devices);
+            free(visited.inodes);
+        }
     }
 
 cleanup:
     // Free allocated memory for each entry
-    for (int i = 0; i < num_entries; i++) {
-        free_file_entry(&entries[i]);
-    }
-    free(entries); // Free the main entries array
+    free_entries(entries, num_entries); // Free the entries and the array
+    free_emoji_config(); // Free user emoji mappings
+    free_ls_colors(); // Free file name colors
     free_args(&args); // Free command-line arguments
 
-    // Return success if entries were found, failure otherwise
-    return num_entries > 0 ? EXIT_SUCCESS : EXIT_FAILURE;
+    // Return success if entries were found without errors, failure otherwise
+    return (num_entries > 0 && !had_error) ? EXIT_SUCCESS : EXIT_FAILURE;
 }
@@ -0,0 +1,56 @@
+#!/bin/sh
+# Time facad on a directory with many entries.
+#
+# Usage: tests/bench_large_dir.sh [FACAD] [COUNT] [RUNS] [LINKS]
+#   FACAD  facad binary to run (default: build/facad)
+#   COUNT  number of files to create (default: 50000)
+#   RUNS   number of timed runs per mode (default: 20)
+#   LINKS  number of files and symbolic links to them in a second
+#          directory (default: 5000)
+#
+# The grid is written to a file, and, when script(1) is available, to a
+# pseudo-terminal, where stdout is line buffered. Run it once before and
+# once after a change to compare them. With strace(1) installed, the
+# stat calls made while listing the symbolic links are counted as well.
+
+set -eu
+
+facad=${1:-build/facad}
+count=${2:-50000}
+runs=${3:-20}
+links=${4:-5000}
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT INT TERM
+
+mkdir "$dir/files"
+(cd "$dir/files" && seq -f "file_%06g.txt" 1 "$count" | xargs touch)
+mkdir "$dir/links"
+(cd "$dir/links" && seq -f "file_%06g.txt" 1 "$links" | xargs touch &&
+    seq -f "file_%06g.txt" 1 "$links" | sed 's/.*/& link_&/' | xargs -n 2 ln -s)
+
+run() {
+    label=$1
+    shift
+    start=$(date +%s.%N)
+    i=0
+    while [ "$i" -lt "$runs" ]; do
+        "$@" > "$dir/out" 2>/dev/null
+        i=$((i + 1))
+    done
+    end=$(date +%s.%N)
+    per_run=$(echo "$start $end $runs" | awk '{ printf "%.3f", ($2 - $1) / $3 }')
+    printf '%-28s %8s s per run\n' "$label" "$per_run"
+}
+
+echo "facad: $facad, $count files, $runs runs"
+run "grid to a file (-C)" "$facad" -C --width 200 "$dir/files"
+run "one row to a file (-C)" "$facad" -C --width 0 "$dir/files"
+if command -v script > /dev/null 2>&1; then
+    run "grid to a terminal (-C)" script -qfc "'$facad' -C --width 200 '$dir/files'" /dev/null
+fi
+run "links (-C)" "$facad" -C --width 200 "$dir/links"
+if command -v strace > /dev/null 2>&1; then
+    strace -f -qq -e trace=%stat -o "$dir/trace" "$facad" -C --width 200 "$dir/links" > /dev/null 2>&1
+    printf '%-28s %8s for %s entries\n' "stat calls on links (-C)" "$(wc -l < "$dir/trace")" "$((links * 2))"
+fi
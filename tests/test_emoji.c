@@ -0,0 +1,47 @@
+/**
+ * @file test_emoji.c
+ * @brief Tests for emoji lookup by file name.
+ *
+ * Checks that extensions are matched ignoring case, so mixed-case names
+ * get the same emoji as their lowercase forms.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+
+#include "emoji_utils.h"
+
+static const char *pairs[][2] = {
+    {"IMG_001.JPG", "img_001.jpg"},  // Image
+    {"Photo.PNG", "photo.png"},      // Image
+    {"a.TAR.GZ", "a.tar.gz"},        // Tarball, a compound extension
+    {"x.Py", "x.py"},                // Source code
+    {"Notes.MD", "notes.md"},        // Document
+};
+
+int main(void) {
+    int failures = 0;
+
+    // The files do not exist, so only their names decide the emoji
+    for (size_t i = 0; i < sizeof(pairs) / sizeof(pairs[0]); i++) {
+        char *mixed = get_emoji(pairs[i][0], S_IFREG | 0644, 0);
+        char *lower = get_emoji(pairs[i][1], S_IFREG | 0644, 0);
+        if (strcmp(mixed, lower) != 0) {
+            fprintf(stderr, "'%s' gets %s but '%s' gets %s\n", pairs[i][0], mixed, pairs[i][1], lower);
+            failures++;
+        }
+        if (strcmp(lower, "❓") == 0) {
+            fprintf(stderr, "'%s' has no emoji of its own\n", pairs[i][1]);
+            failures++;
+        }
+        free(mixed);
+        free(lower);
+    }
+
+    return failures == 0 ? 0 : 1;
+}
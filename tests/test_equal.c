@@ -0,0 +1,164 @@
+/**
+ * @file test_equal.c
+ * @brief Tests for recognising the same file under different names.
+ *
+ * Checks that file_entries_equal tells apart files that share a name in
+ * different directories, differ only in case or are hard links to each
+ * other, and matches paths that normalize to the same path.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+#include <sys/stat.h>
+
+#include "file_card.h"
+
+#define SKIP 77  // Exit status meson reports as a skipped test
+
+static char dir[] = "/tmp/facad_test_equal_XXXXXX";
+
+/**
+ * @brief Builds the full path of a file in the test directory.
+ *
+ * @param path Buffer of at least 4096 bytes for the result
+ * @param name Path of the file relative to the test directory
+ * @return path
+ */
+static char *full_path(char *path, const char *name) {
+    snprintf(path, 4096, "%s/%s", dir, name);
+    return path;
+}
+
+/**
+ * @brief Creates an empty file inside the test directory.
+ *
+ * @param name Path of the file relative to the test directory
+ * @return 0 on success, -1 on failure
+ */
+static int make_file(const char *name) {
+    char path[4096];
+    FILE *file = fopen(full_path(path, name), "w");
+    if (!file) {
+        perror(path);
+        return -1;
+    }
+    fclose(file);
+    return 0;
+}
+
+/**
+ * @brief Compares the entries of two files in the test directory.
+ *
+ * Entries are created by name inside their directory, the way directory
+ * listings create them, so both can carry the same name.
+ *
+ * @param dir_a Directory of the first file relative to the test directory
+ * @param name_a Name of the first file
+ * @param dir_b Directory of the second file relative to the test directory
+ * @param name_b Name of the second file
+ * @param expected 1 if the files should be equal, 0 otherwise
+ * @return 0 if the result matches, 1 otherwise
+ */
+static int check_equal(const char *dir_a, const char *name_a, const char *dir_b, const char *name_b, int expected) {
+    char path_a[4096], path_b[4096];
+    FileCardInfo a, b;
+    if (create_file_entry_in(&a, full_path(path_a, dir_a), name_a) != 0) {
+        fprintf(stderr, "cannot examine '%s/%s'\n", dir_a, name_a);
+        return 1;
+    }
+    if (create_file_entry_in(&b, full_path(path_b, dir_b), name_b) != 0) {
+        fprintf(stderr, "cannot examine '%s/%s'\n", dir_b, name_b);
+        free_file_entry(&a);
+        return 1;
+    }
+
+    int equal = file_entries_equal(&a, &b);
+    free_file_entry(&a);
+    free_file_entry(&b);
+    if (equal != expected) {
+        fprintf(stderr, "'%s/%s' and '%s/%s' are %s, expected %s\n", dir_a, name_a, dir_b, name_b,
+                equal ? "equal" : "different", expected ? "equal" : "different");
+        return 1;
+    }
+    return 0;
+}
+
+/**
+ * @brief Removes a file from the test directory, ignoring errors.
+ *
+ * @param name Path of the file relative to the test directory
+ */
+static void remove_file(const char *name) {
+    char path[4096];
+    remove(full_path(path, name));
+}
+
+int main(void) {
+    if (!mkdtemp(dir)) {
+        perror("mkdtemp");
+        return EXIT_FAILURE;
+    }
+
+    int status = EXIT_FAILURE;
+    char path_a[4096], path_b[4096];
+    if (make_file("README") != 0 || make_file("readme") != 0) {
+        goto cleanup;
+    }
+
+    // A case-insensitive file system has only one file for both names
+    struct stat upper, lower;
+    if (stat(full_path(path_a, "README"), &upper) != 0 || stat(full_path(path_b, "readme"), &lower) != 0) {
+        perror("stat");
+        goto cleanup;
+    }
+    if (upper.st_dev == lower.st_dev && upper.st_ino == lower.st_ino) {
+        fprintf(stderr, "file system ignores case, skipping\n");
+        status = SKIP;
+        goto cleanup;
+    }
+
+    if (mkdir(full_path(path_a, "a"), 0755) != 0 || mkdir(full_path(path_b, "b"), 0755) != 0 ||
+        make_file("a/README") != 0 || make_file("b/README") != 0) {
+        goto cleanup;
+    }
+    if (link(full_path(path_a, "README"), full_path(path_b, "link")) != 0) {
+        perror("link");
+        goto cleanup;
+    }
+
+    int failures = 0;
+    failures += check_equal(".", "README", ".", "readme", 0);
+    failures += check_equal("a", "README", "b", "README", 0);
+    failures += check_equal(".", "README", ".", "README", 1);
+    failures += check_equal(".", "README", "a/..", "README", 1);
+    failures += check_equal(".", "README", "./a/", "../README", 1);
+    failures += check_equal(".", "README", ".", "link", 0);  // Another name for the same inode
+
+    // Placeholders carry no identity, so they match nothing
+    FileCardInfo unknown, other;
+    if (create_unknown_file_entry(&unknown, "README") == 0 && create_unknown_file_entry(&other, "README") == 0) {
+        if (file_entries_equal(&unknown, &other)) {
+            fprintf(stderr, "two unknown entries are equal\n");
+            failures++;
+        }
+        free_file_entry(&unknown);
+        free_file_entry(&other);
+    }
+
+    status = failures == 0 ? EXIT_SUCCESS : EXIT_FAILURE;
+
+cleanup:
+    remove_file("link");
+    remove_file("a/README");
+    remove_file("b/README");
+    remove_file("a");
+    remove_file("b");
+    remove_file("readme");
+    remove_file("README");
+    rmdir(dir);
+    return status;
+}
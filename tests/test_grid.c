@@ -0,0 +1,131 @@
+/**
+ * @file test_grid.c
+ * @brief Tests for the grid layout helpers.
+ *
+ * Checks that entries filled top to bottom are spread over the columns
+ * so that no column is more than one entry longer than another, and that
+ * the number of columns follows the terminal width instead of a fixed cap.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+
+#include "display_utils.h"
+
+#define NUM_SHORT 20
+
+/**
+ * @brief Counts the lines of a grid rendered in plain mode.
+ *
+ * @param names Names of the entries.
+ * @param num_names Number of names.
+ * @param term_width The width to render for.
+ * @return The number of lines, or -1 if rendering failed.
+ */
+static int count_lines(const char **names, int num_names, int term_width) {
+    FileCardInfo *entries = calloc(num_names, sizeof(FileCardInfo));
+    if (!entries) {
+        return -1;
+    }
+    for (int i = 0; i < num_names; i++) {
+        entries[i].name = (char *)names[i];
+        entries[i].display_name = (char *)names[i];
+        entries[i].emoji = "📝";
+        entries[i].mode = S_IFREG | 0644;
+    }
+
+    DisplayOptions options = {.plain = 1};
+    char *text = render_entries(entries, num_names, term_width, ".", &options);
+    free(entries);
+    if (!text) {
+        return -1;
+    }
+
+    int lines = 0;
+    for (const char *p = text; *p; p++) {
+        lines += *p == '\n';
+    }
+    free(text);
+    return lines;
+}
+
+/**
+ * @brief Checks how many rows short and long names take.
+ *
+ * @return The number of failed checks.
+ */
+static int check_column_count(void) {
+    static const char *short_names[NUM_SHORT] = {
+        "a", "b", "c", "d", "e", "f", "g", "h", "i", "j",
+        "k", "l", "m", "n", "o", "p", "q", "r", "s", "t",
+    };
+    int failures = 0;
+
+    // Twenty one-letter names take 58 columns, more than four fit in 80
+    int lines = count_lines(short_names, NUM_SHORT, 80);
+    if (lines != 1) {
+        fprintf(stderr, "%d short names at width 80: %d lines, expected 1\n", NUM_SHORT, lines);
+        failures++;
+    }
+    lines = count_lines(short_names, NUM_SHORT, 20);
+    if (lines != 3) {
+        fprintf(stderr, "%d short names at width 20: %d lines, expected 3\n", NUM_SHORT, lines);
+        failures++;
+    }
+
+    // A name wider than the terminal still gets a column of its own
+    char long_name[201];
+    memset(long_name, 'x', sizeof(long_name) - 1);
+    long_name[sizeof(long_name) - 1] = '\0';
+    const char *mixed[] = {"a", long_name, "b"};
+    lines = count_lines(mixed, 3, 80);
+    if (lines != 3) {
+        fprintf(stderr, "a long name at width 80: %d lines, expected 3\n", lines);
+        failures++;
+    }
+    return failures;
+}
+
+int main(void) {
+    int failures = check_column_count();
+
+    for (int n = 1; n <= 50; n++) {
+        for (int columns = 1; columns <= n; columns++) {
+            int shortest = n;
+            int longest = 0;
+            int next_start = 0;
+
+            for (int col = 0; col < columns; col++) {
+                int length = column_length(col, n, columns);
+                if (column_start(col, n, columns) != next_start) {
+                    fprintf(stderr, "n=%d columns=%d: column %d does not follow the previous one\n", n, columns, col);
+                    failures++;
+                }
+                next_start += length;
+                if (length < shortest) shortest = length;
+                if (length > longest) longest = length;
+            }
+
+            if (next_start != n) {
+                fprintf(stderr, "n=%d columns=%d: columns hold %d entries\n", n, columns, next_start);
+                failures++;
+            }
+            if (longest - shortest > 1) {
+                fprintf(stderr, "n=%d columns=%d: columns of %d and %d entries\n", n, columns, shortest, longest);
+                failures++;
+            }
+            if (grid_rows(n, columns, 0) != longest) {
+                fprintf(stderr, "n=%d columns=%d: %d rows for a column of %d\n", n, columns,
+                        grid_rows(n, columns, 0), longest);
+                failures++;
+            }
+        }
+    }
+
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,110 @@
+/**
+ * @file test_width.c
+ * @brief Tests for display width calculations.
+ *
+ * Checks that measuring and cutting names agree for emoji made of several
+ * code points, and that such names and two-glyph link emojis never make
+ * grid lines wider than the terminal or the padding wrap around.
+ *
+ * @author Sergey Veneckiy
+ * @date 2024
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+
+#include "display_utils.h"
+
+static const char *names[] = {
+    "👨‍👩‍👧.txt",   // Family emoji joined with zero-width joiners
+    "🇺🇦",         // Flag made of two regional indicators
+    "👍🏽 ok",      // Emoji with a skin tone modifier
+    "❤️.md",       // Emoji with a variation selector
+    "cafe\xcc\x81",  // Combining acute accent after the e
+    "日本語.txt",
+    "plain.txt",
+};
+
+#define NUM_NAMES (int)(sizeof(names) / sizeof(names[0]))
+
+/**
+ * @brief Checks that fit_to_width and get_display_width agree on a name.
+ *
+ * @param name The name to check.
+ * @return The number of failed checks.
+ */
+static int check_fit(const char *name) {
+    int failures = 0;
+    size_t width = get_display_width(name);
+
+    // The full width fits the whole name
+    size_t prefix_width = 0;
+    size_t length = fit_to_width(name, width, &prefix_width);
+    if (length != strlen(name) || prefix_width != width) {
+        fprintf(stderr, "'%s': width %zu fits %zu of %zu bytes with width %zu\n",
+                name, width, length, strlen(name), prefix_width);
+        failures++;
+    }
+
+    // Every cut is measured the same way by both functions
+    for (size_t max_width = 0; max_width < width; max_width++) {
+        length = fit_to_width(name, max_width, &prefix_width);
+        char *prefix = strndup(name, length);
+        if (prefix_width > max_width || get_display_width(prefix) != prefix_width) {
+            fprintf(stderr, "'%s': cut to %zu has width %zu, measured %zu\n",
+                    name, max_width, prefix_width, get_display_width(prefix));
+            failures++;
+        }
+        free(prefix);
+    }
+    return failures;
+}
+
+/**
+ * @brief Checks that a rendered grid of the names fits its width.
+ *
+ * A padding that wrapped around would print an enormous run of spaces.
+ *
+ * @param term_width The width to render for.
+ * @return The number of failed checks.
+ */
+static int check_grid(int term_width) {
+    FileCardInfo entries[NUM_NAMES];
+    for (int i = 0; i < NUM_NAMES; i++) {
+        memset(&entries[i], 0, sizeof(entries[i]));
+        entries[i].name = (char *)names[i];
+        entries[i].display_name = (char *)names[i];
+        entries[i].emoji = i % 3 == 0 ? "🔗📁" : "📝";  // Link emojis are four columns wide
+        entries[i].mode = S_IFREG | 0644;
+    }
+
+    DisplayOptions options = {.max_columns = 4, .truncate_names = 1};
+    char *text = render_entries(entries, NUM_NAMES, term_width, ".", &options);
+    if (!text) {
+        fprintf(stderr, "width %d: rendering failed\n", term_width);
+        return 1;
+    }
+
+    int failures = 0;
+    for (char *line = strtok(text, "\n"); line; line = strtok(NULL, "\n")) {
+        if (get_display_width(line) > (size_t)term_width) {
+            fprintf(stderr, "width %d: line of width %zu: '%s'\n", term_width, get_display_width(line), line);
+            failures++;
+        }
+    }
+    free(text);
+    return failures;
+}
+
+int main(void) {
+    int failures = 0;
+    for (int i = 0; i < NUM_NAMES; i++) {
+        failures += check_fit(names[i]);
+    }
+    for (int term_width = 8; term_width <= 80; term_width++) {
+        failures += check_grid(term_width);
+    }
+    return failures == 0 ? 0 : 1;
+}